@@ -1,6 +1,8 @@
 use wasm_bindgen::prelude::*;
 
+use super::color::{LuminanceWeights, pack_rgba, unpack_rgba};
 use super::format::ImageFormat;
+use super::sampling::{SamplingMode, sample};
 
 /// SharedBuffer - 一个可以在 Rust 和 JS 之间共享的内存区域
 #[wasm_bindgen]
@@ -14,15 +16,11 @@ pub struct SharedBuffer {
 #[wasm_bindgen]
 impl SharedBuffer {
     /// 创建一个新的 SharedBuffer，根据 宽、高、格式 分配内存
+    ///
+    /// 所需字节数溢出或超过 4 GiB 时 panic，需要处理错误时使用 `try_new`
     #[wasm_bindgen(constructor)]
     pub fn new(width: u32, height: u32, format: ImageFormat) -> SharedBuffer {
-        let size = (width * height * (format as u32)) as usize;
-        SharedBuffer {
-            width,
-            height,
-            format,
-            buffer: vec![0; size],
-        }
+        Self::try_new(width, height, format).unwrap_or_else(|e| panic!("{e}"))
     }
 
     /// 返回 buffer 的指针，供 JS 端访问
@@ -35,6 +33,11 @@ impl SharedBuffer {
         self.buffer.len()
     }
 
+    /// 检查 buffer 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
     /// 返回图像宽度
     pub fn width(&self) -> u32 {
         self.width
@@ -44,12 +47,311 @@ impl SharedBuffer {
     pub fn height(&self) -> u32 {
         self.height
     }
+
+    /// 清空 buffer（全部置零）
+    ///
+    /// RGBA 为全透明，RGB/灰度为黑色，适合双缓冲管线在帧间快速清屏
+    pub fn clear(&mut self) {
+        self.buffer.fill(0);
+    }
+
+    /// 用单一颜色（`0xRRGGBBAA`）填充 buffer
+    ///
+    /// 按 buffer 格式写入：RGB 丢弃 alpha，灰度按 Rec. 601 亮度换算并丢弃 alpha
+    pub fn fill_color(&mut self, color: u32) {
+        let bpp = self.format.bytes_per_pixel();
+        let pixel = self.native_color(color);
+        for chunk in self.buffer.chunks_exact_mut(bpp) {
+            chunk.copy_from_slice(&pixel[..bpp]);
+        }
+    }
+
+    /// 读取像素，返回 `0xRRGGBBAA`，坐标越界时返回 0
+    ///
+    /// RGB 的 alpha 视为 255，灰度展开为 (l, l, l, 255)
+    pub fn get_pixel(&self, x: u32, y: u32) -> u32 {
+        let Some(idx) = self.pixel_offset(x, y) else {
+            return 0;
+        };
+        let [r, g, b, a] = decode_pixel(self.format, &self.buffer[idx..]);
+        pack_rgba(r, g, b, a)
+    }
+
+    /// 写入像素（`0xRRGGBBAA`），坐标越界时忽略
+    ///
+    /// 与 `fill_color` 相同地转换为 buffer 格式
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: u32) {
+        let Some(idx) = self.pixel_offset(x, y) else {
+            return;
+        };
+        let bpp = self.format.bytes_per_pixel();
+        let pixel = self.native_color(color);
+        self.buffer[idx..idx + bpp].copy_from_slice(&pixel[..bpp]);
+    }
+
+    /// 转换为另一种格式，返回新的 buffer，宽高不变
+    ///
+    /// 灰度展开到 RGB 三个通道，缺少 alpha 时补 255；
+    /// 转为灰度按 Rec. 601 亮度换算，转为 RGB/灰度时丢弃 alpha
+    pub fn convert_to(&self, target: ImageFormat) -> SharedBuffer {
+        self.convert_to_with_weights(target, LuminanceWeights::default())
+    }
+
+    /// 转换为另一种格式，转为灰度时使用指定的亮度权重
+    pub fn convert_to_with_weights(
+        &self,
+        target: ImageFormat,
+        weights: LuminanceWeights,
+    ) -> SharedBuffer {
+        let buffer = if target == self.format {
+            self.buffer.clone()
+        } else {
+            let (src_bpp, dst_bpp) = (self.format.bytes_per_pixel(), target.bytes_per_pixel());
+            let mut buffer = Vec::with_capacity(self.buffer.len() / src_bpp * dst_bpp);
+            for pixel in self.buffer.chunks_exact(src_bpp) {
+                let encoded = encode_pixel(target, decode_pixel(self.format, pixel), weights);
+                buffer.extend_from_slice(&encoded[..dst_bpp]);
+            }
+            buffer
+        };
+
+        SharedBuffer {
+            width: self.width,
+            height: self.height,
+            format: target,
+            buffer,
+        }
+    }
+
+    /// 用随机数据填充 buffer（噪声纹理、测试图案等）
+    ///
+    /// 启用 `random` 特性时使用 `getrandom` 获取系统随机数；
+    /// 未启用时使用固定种子的伪随机序列，每次调用结果相同
+    pub fn refresh(&mut self) {
+        #[cfg(feature = "random")]
+        if getrandom::getrandom(&mut self.buffer).is_ok() {
+            return;
+        }
+        fill_pseudo_random(&mut self.buffer);
+    }
+
+    /// 调整图像尺寸，按指定采样模式重采样
+    ///
+    /// # Arguments
+    /// * `width` - 新宽度
+    /// * `height` - 新高度
+    /// * `mode` - 采样模式（`Nearest` 适合像素画放大，`Bilinear` 适合照片）
+    ///
+    /// 所需字节数溢出或超过 4 GiB 时 panic，需要处理错误时使用 `try_resize`
+    pub fn resize(&mut self, width: u32, height: u32, mode: SamplingMode) {
+        self.try_resize(width, height, mode)
+            .unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// 顺时针旋转 90 度（宽高互换）
+    pub fn rotate_90(&mut self) {
+        let (w, h) = (self.width, self.height);
+        self.remap_pixels(h, w, |x, y| (h - 1 - y, x));
+    }
+
+    /// 旋转 180 度
+    pub fn rotate_180(&mut self) {
+        let (w, h) = (self.width, self.height);
+        self.remap_pixels(w, h, |x, y| (w - 1 - x, h - 1 - y));
+    }
+
+    /// 顺时针旋转 270 度（即逆时针 90 度，宽高互换）
+    pub fn rotate_270(&mut self) {
+        let (w, h) = (self.width, self.height);
+        self.remap_pixels(h, w, |x, y| (y, w - 1 - x));
+    }
+}
+
+impl SharedBuffer {
+    /// 创建 SharedBuffer，所需字节数溢出或超过 4 GiB 时返回错误
+    pub fn try_new(width: u32, height: u32, format: ImageFormat) -> Result<SharedBuffer, String> {
+        let size = format.buffer_size(width, height)?;
+        Ok(SharedBuffer {
+            width,
+            height,
+            format,
+            buffer: vec![0; size],
+        })
+    }
+
+    /// 调整图像尺寸，所需字节数溢出或超过 4 GiB 时返回错误且不修改 buffer
+    pub fn try_resize(
+        &mut self,
+        width: u32,
+        height: u32,
+        mode: SamplingMode,
+    ) -> Result<(), String> {
+        let channels = self.format as usize;
+        let size = self.format.buffer_size(width, height)?;
+        let mut resized = vec![0; size];
+
+        if self.width > 0 && self.height > 0 {
+            let scale_x = self.width as f32 / width as f32;
+            let scale_y = self.height as f32 / height as f32;
+
+            for y in 0..height {
+                // 目标像素中心映射回源图像坐标
+                let sy = (y as f32 + 0.5) * scale_y;
+                for x in 0..width {
+                    let sx = (x as f32 + 0.5) * scale_x;
+                    let idx = (y * width + x) as usize * channels;
+                    sample(
+                        &self.buffer,
+                        self.width,
+                        self.height,
+                        channels,
+                        sx,
+                        sy,
+                        mode,
+                        &mut resized[idx..idx + channels],
+                    );
+                }
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.buffer = resized;
+        Ok(())
+    }
+
+    /// 接管已有的像素数据创建 SharedBuffer（不复制）
+    ///
+    /// # Arguments
+    /// * `width` - 图像宽度
+    /// * `height` - 图像高度
+    /// * `format` - 图像格式
+    /// * `data` - 像素数据，长度必须为 `width * height * 通道数`
+    pub fn from_vec(
+        width: u32,
+        height: u32,
+        format: ImageFormat,
+        data: Vec<u8>,
+    ) -> Result<SharedBuffer, String> {
+        let expected = format.buffer_size(width, height)?;
+        if data.len() != expected {
+            return Err(format!(
+                "buffer length {} does not match {}x{} {:?} (expected {})",
+                data.len(),
+                width,
+                height,
+                format,
+                expected
+            ));
+        }
+
+        Ok(SharedBuffer {
+            width,
+            height,
+            format,
+            buffer: data,
+        })
+    }
+
+    /// 取出内部像素数据（不复制）
+    pub fn into_vec(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    /// 像素在 buffer 中的字节偏移，越界时返回 `None`
+    fn pixel_offset(&self, x: u32, y: u32) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| {
+            (y as usize * self.width as usize + x as usize) * self.format.bytes_per_pixel()
+        })
+    }
+
+    /// 将 `0xRRGGBBAA` 转换为 buffer 格式的字节（只有前 `bytes_per_pixel` 个有效）
+    pub(super) fn native_color(&self, color: u32) -> [u8; 4] {
+        let (r, g, b, a) = unpack_rgba(color);
+        encode_pixel(self.format, [r, g, b, a], LuminanceWeights::default())
+    }
+
+    /// 按坐标映射无损重排像素
+    ///
+    /// `map` 将源坐标 (x, y) 映射为新图像中的坐标
+    fn remap_pixels(
+        &mut self,
+        new_width: u32,
+        new_height: u32,
+        map: impl Fn(u32, u32) -> (u32, u32),
+    ) {
+        let bpp = self.format as usize;
+        let mut remapped = vec![0; self.buffer.len()];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = map(x, y);
+                let src = (y * self.width + x) as usize * bpp;
+                let dst = (ny * new_width + nx) as usize * bpp;
+                remapped[dst..dst + bpp].copy_from_slice(&self.buffer[src..src + bpp]);
+            }
+        }
+
+        self.width = new_width;
+        self.height = new_height;
+        self.buffer = remapped;
+    }
+}
+
+/// 将指定格式的像素展开为 RGBA（无 alpha 时视为 255）
+fn decode_pixel(format: ImageFormat, p: &[u8]) -> [u8; 4] {
+    match format {
+        ImageFormat::Rgba => [p[0], p[1], p[2], p[3]],
+        ImageFormat::Rgb => [p[0], p[1], p[2], 255],
+        ImageFormat::Grayscale => [p[0], p[0], p[0], 255],
+    }
+}
+
+/// 将 RGBA 转换为指定格式的字节（只有前 `bytes_per_pixel` 个有效），灰度按 `weights` 换算
+fn encode_pixel(format: ImageFormat, [r, g, b, a]: [u8; 4], weights: LuminanceWeights) -> [u8; 4] {
+    match format {
+        ImageFormat::Rgba => [r, g, b, a],
+        ImageFormat::Rgb => [r, g, b, 0],
+        ImageFormat::Grayscale => [weights.luminance(r, g, b), 0, 0, 0],
+    }
+}
+
+/// 用固定种子的 xorshift32 序列填充数据
+fn fill_pseudo_random(data: &mut [u8]) {
+    let mut state: u32 = 0x2545_F491;
+    for chunk in data.chunks_mut(4) {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        let bytes = state.to_le_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    #[cfg(not(feature = "random"))]
+    fn test_refresh_deterministic_without_random_feature() {
+        let mut a = SharedBuffer::new(5, 3, ImageFormat::Rgba);
+        let mut b = SharedBuffer::new(5, 3, ImageFormat::Rgba);
+        a.refresh();
+        b.refresh();
+
+        assert_eq!(a.into_vec(), b.into_vec());
+    }
+
+    #[test]
+    fn test_refresh_fills_buffer() {
+        let mut buffer = SharedBuffer::new(8, 8, ImageFormat::Rgba);
+        buffer.refresh();
+
+        assert_eq!(buffer.len(), 8 * 8 * 4);
+        assert!(buffer.into_vec().iter().any(|&v| v != 0));
+    }
+
     #[test]
     fn test_shared_buffer_new_rgba() {
         let buffer = SharedBuffer::new(100, 100, ImageFormat::Rgba);
@@ -58,6 +360,125 @@ mod tests {
         assert_eq!(buffer.len(), 100 * 100 * 4);
     }
 
+    #[test]
+    fn test_fill_color_per_format() {
+        let mut rgba = SharedBuffer::new(2, 1, ImageFormat::Rgba);
+        rgba.fill_color(0x11223344);
+        assert_eq!(
+            rgba.into_vec(),
+            vec![0x11, 0x22, 0x33, 0x44, 0x11, 0x22, 0x33, 0x44]
+        );
+
+        let mut rgb = SharedBuffer::new(2, 1, ImageFormat::Rgb);
+        rgb.fill_color(0x11223344);
+        assert_eq!(rgb.into_vec(), vec![0x11, 0x22, 0x33, 0x11, 0x22, 0x33]);
+
+        // 灰度按 Rec. 601 亮度：0.299 * 255 ≈ 76
+        let mut gray = SharedBuffer::new(3, 1, ImageFormat::Grayscale);
+        gray.fill_color(0xFF0000FF);
+        assert_eq!(gray.into_vec(), vec![76, 76, 76]);
+
+        let mut cleared = SharedBuffer::new(2, 2, ImageFormat::Rgba);
+        cleared.fill_color(0xFFFFFFFF);
+        cleared.clear();
+        assert!(cleared.into_vec().iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    fn test_get_set_pixel() {
+        let mut rgba = SharedBuffer::new(3, 2, ImageFormat::Rgba);
+        rgba.set_pixel(2, 1, 0x11223344);
+        assert_eq!(rgba.get_pixel(2, 1), 0x11223344);
+        assert_eq!(rgba.get_pixel(0, 0), 0);
+
+        // 越界读返回 0，越界写被忽略
+        rgba.set_pixel(3, 0, 0xFFFFFFFF);
+        rgba.set_pixel(0, 2, 0xFFFFFFFF);
+        assert_eq!(rgba.get_pixel(3, 0), 0);
+        assert_eq!(rgba.buffer.iter().filter(|&&v| v != 0).count(), 4);
+
+        let mut rgb = SharedBuffer::new(2, 2, ImageFormat::Rgb);
+        rgb.set_pixel(1, 1, 0x11223300);
+        assert_eq!(rgb.get_pixel(1, 1), 0x112233FF);
+        assert_eq!(&rgb.buffer[9..12], &[0x11, 0x22, 0x33]);
+
+        let mut gray = SharedBuffer::new(2, 2, ImageFormat::Grayscale);
+        gray.set_pixel(0, 1, 0x808080FF);
+        assert_eq!(gray.buffer[2], 0x80);
+        assert_eq!(gray.get_pixel(0, 1), 0x808080FF);
+    }
+
+    #[test]
+    fn test_convert_to_all_formats() {
+        use ImageFormat::{Grayscale, Rgb, Rgba};
+
+        let source = |format| {
+            let mut buffer = SharedBuffer::new(2, 1, format);
+            buffer.set_pixel(0, 0, pack_rgba(200, 100, 50, 128));
+            buffer.set_pixel(1, 0, pack_rgba(10, 20, 30, 255));
+            buffer
+        };
+        // 200/100/50 的 Rec. 601 亮度为 124，10/20/30 为 18
+        let cases: [(ImageFormat, ImageFormat, Vec<u8>); 9] = [
+            (Rgba, Rgba, vec![200, 100, 50, 128, 10, 20, 30, 255]),
+            (Rgba, Rgb, vec![200, 100, 50, 10, 20, 30]),
+            (Rgba, Grayscale, vec![124, 18]),
+            (Rgb, Rgba, vec![200, 100, 50, 255, 10, 20, 30, 255]),
+            (Rgb, Rgb, vec![200, 100, 50, 10, 20, 30]),
+            (Rgb, Grayscale, vec![124, 18]),
+            (Grayscale, Rgba, vec![124, 124, 124, 255, 18, 18, 18, 255]),
+            (Grayscale, Rgb, vec![124, 124, 124, 18, 18, 18]),
+            (Grayscale, Grayscale, vec![124, 18]),
+        ];
+
+        for (from, to, expected) in cases {
+            let converted = source(from).convert_to(to);
+            assert_eq!((converted.width(), converted.height()), (2, 1));
+            assert_eq!(converted.format, to);
+            assert_eq!(converted.into_vec(), expected, "{from:?} -> {to:?}");
+        }
+    }
+
+    #[test]
+    fn test_convert_to_grayscale_with_weights() {
+        let mut green = SharedBuffer::new(1, 1, ImageFormat::Rgba);
+        green.fill_color(pack_rgba(0, 255, 0, 255));
+        let rec601 =
+            green.convert_to_with_weights(ImageFormat::Grayscale, LuminanceWeights::Rec601);
+        let rec709 =
+            green.convert_to_with_weights(ImageFormat::Grayscale, LuminanceWeights::Rec709);
+        assert_eq!(rec601.into_vec(), vec![150]);
+        assert_eq!(rec709.into_vec(), vec![182]);
+        assert_eq!(
+            green.convert_to(ImageFormat::Grayscale).into_vec(),
+            vec![150]
+        );
+    }
+
+    #[test]
+    fn test_try_new_rejects_oversized_buffer() {
+        // 40000x40000 RGBA 在 u32 下会回绕成一个过小的 buffer
+        assert!(SharedBuffer::try_new(40000, 40000, ImageFormat::Rgba).is_err());
+        let buffer = SharedBuffer::try_new(3, 2, ImageFormat::Rgb).unwrap();
+        assert_eq!(buffer.len(), 18);
+    }
+
+    #[test]
+    fn test_oversized_from_vec_and_resize_rejected() {
+        // 65536x65536 RGBA 在 32 位 usize 下会回绕为 0
+        assert!(SharedBuffer::from_vec(65536, 65536, ImageFormat::Rgba, vec![]).is_err());
+
+        let mut buffer = SharedBuffer::new(2, 2, ImageFormat::Rgb);
+        assert!(
+            buffer
+                .try_resize(65536, 65536, SamplingMode::Nearest)
+                .is_err()
+        );
+        assert_eq!((buffer.width(), buffer.height(), buffer.len()), (2, 2, 12));
+        assert!(buffer.try_resize(3, 1, SamplingMode::Nearest).is_ok());
+        assert_eq!(buffer.len(), 9);
+    }
+
     #[test]
     fn test_shared_buffer_new_rgb() {
         let buffer = SharedBuffer::new(50, 50, ImageFormat::Rgb);
@@ -67,6 +488,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::identity_op)]
     fn test_shared_buffer_new_grayscale() {
         let buffer = SharedBuffer::new(32, 32, ImageFormat::Grayscale);
         assert_eq!(buffer.width(), 32);
@@ -100,4 +522,129 @@ mod tests {
             assert_eq!(buffer.len(), (w * h * 4) as usize);
         }
     }
+
+    #[test]
+    fn test_clear_zeroes_rgba() {
+        let mut buffer = SharedBuffer::new(4, 4, ImageFormat::Rgba);
+        buffer.buffer.fill(200);
+        buffer.clear();
+        assert_eq!(buffer.len(), 4 * 4 * 4);
+        assert!(buffer.buffer.iter().all(|&v| v == 0));
+    }
+
+    /// 创建 2x2 灰度棋盘格：黑白交替
+    fn checkerboard_2x2() -> SharedBuffer {
+        let mut buffer = SharedBuffer::new(2, 2, ImageFormat::Grayscale);
+        buffer.buffer.copy_from_slice(&[0, 255, 255, 0]);
+        buffer
+    }
+
+    #[test]
+    fn test_resize_nearest_hard_edges() {
+        let mut buffer = checkerboard_2x2();
+        buffer.resize(4, 4, SamplingMode::Nearest);
+
+        assert_eq!(buffer.width(), 4);
+        assert_eq!(buffer.height(), 4);
+        assert_eq!(buffer.len(), 16);
+
+        // 每个源像素放大为 2x2 块，只有纯黑和纯白
+        #[rustfmt::skip]
+        let expected = [
+            0, 0, 255, 255,
+            0, 0, 255, 255,
+            255, 255, 0, 0,
+            255, 255, 0, 0,
+        ];
+        assert_eq!(buffer.buffer, expected);
+    }
+
+    #[test]
+    fn test_resize_bilinear_intermediate_values() {
+        let mut buffer = checkerboard_2x2();
+        buffer.resize(4, 4, SamplingMode::Bilinear);
+
+        assert_eq!(buffer.len(), 16);
+
+        // 内部像素应出现介于黑白之间的过渡值
+        let center = buffer.buffer[4 + 1];
+        assert!(center > 0 && center < 255);
+        assert!(buffer.buffer.iter().any(|&v| v != 0 && v != 255));
+    }
+
+    #[test]
+    fn test_rotate_90_moves_corner() {
+        // 3x2 RGB 图像，左上角像素为红色
+        let mut buffer = SharedBuffer::new(3, 2, ImageFormat::Rgb);
+        buffer.buffer[0..3].copy_from_slice(&[255, 0, 0]);
+
+        buffer.rotate_90();
+        assert_eq!(buffer.width(), 2);
+        assert_eq!(buffer.height(), 3);
+
+        // 顺时针旋转后左上角移到右上角
+        // 新坐标 (1, 0)，每像素 3 字节
+        let idx = 3;
+        assert_eq!(&buffer.buffer[idx..idx + 3], &[255, 0, 0]);
+        assert_eq!(&buffer.buffer[0..3], &[0, 0, 0]);
+    }
+
+    #[test]
+    fn test_rotate_180_and_270() {
+        let mut buffer = SharedBuffer::new(2, 3, ImageFormat::Grayscale);
+        buffer.buffer.copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+
+        let mut rotated = SharedBuffer::new(2, 3, ImageFormat::Grayscale);
+        rotated.buffer.copy_from_slice(&buffer.buffer);
+        rotated.rotate_180();
+        assert_eq!(rotated.buffer, [6, 5, 4, 3, 2, 1]);
+
+        buffer.rotate_270();
+        assert_eq!(buffer.width(), 3);
+        assert_eq!(buffer.height(), 2);
+        assert_eq!(buffer.buffer, [2, 4, 6, 1, 3, 5]);
+    }
+
+    #[test]
+    fn test_rotate_full_turn_is_identity() {
+        let mut buffer = SharedBuffer::new(4, 3, ImageFormat::Rgba);
+        for (i, byte) in buffer.buffer.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let original = buffer.buffer.clone();
+
+        buffer.rotate_90();
+        buffer.rotate_90();
+        buffer.rotate_180();
+        assert_eq!(buffer.width(), 4);
+        assert_eq!(buffer.buffer, original);
+    }
+
+    #[test]
+    fn test_from_vec_round_trip() {
+        let data: Vec<u8> = (0..2 * 3 * 3).collect();
+        let ptr = data.as_ptr();
+
+        let buffer = SharedBuffer::from_vec(2, 3, ImageFormat::Rgb, data).unwrap();
+        assert_eq!(buffer.width(), 2);
+        assert_eq!(buffer.height(), 3);
+        // 未发生复制
+        assert_eq!(buffer.ptr(), ptr);
+
+        let data = buffer.into_vec();
+        assert_eq!(data, (0..18).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_from_vec_rejects_wrong_length() {
+        let result = SharedBuffer::from_vec(2, 2, ImageFormat::Rgba, vec![0; 15]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_resize_keeps_format_channels() {
+        let mut buffer = SharedBuffer::new(3, 3, ImageFormat::Rgb);
+        buffer.resize(5, 2, SamplingMode::Bilinear);
+        assert_eq!(buffer.len(), 5 * 2 * 3);
+    }
 }
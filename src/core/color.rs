@@ -0,0 +1,104 @@
+use wasm_bindgen::prelude::*;
+
+/// 亮度权重枚举
+/// 定义 RGB 转灰度时各通道的权重
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LuminanceWeights {
+    /// Rec. 601（0.299, 0.587, 0.114），标清视频标准，默认值
+    #[default]
+    Rec601 = 0,
+    /// Rec. 709（0.2126, 0.7152, 0.0722），高清/线性管线标准
+    Rec709 = 1,
+    /// 三通道平均
+    Average = 2,
+}
+
+impl LuminanceWeights {
+    /// 获取 (R, G, B) 通道权重
+    #[inline]
+    pub fn coefficients(&self) -> [f32; 3] {
+        match self {
+            LuminanceWeights::Rec601 => [0.299, 0.587, 0.114],
+            LuminanceWeights::Rec709 => [0.2126, 0.7152, 0.0722],
+            LuminanceWeights::Average => [1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0],
+        }
+    }
+
+    /// 计算像素亮度
+    #[inline]
+    pub fn luminance(&self, r: u8, g: u8, b: u8) -> u8 {
+        let [wr, wg, wb] = self.coefficients();
+        (r as f32 * wr + g as f32 * wg + b as f32 * wb)
+            .round()
+            .clamp(0.0, 255.0) as u8
+    }
+}
+
+/// 将 RGBA 分量打包为 `0xRRGGBBAA`
+///
+/// 本库所有整数颜色（背景色、精灵颜色、线段颜色等）都使用这一字节序
+#[inline]
+pub fn pack_rgba(r: u8, g: u8, b: u8, a: u8) -> u32 {
+    u32::from_be_bytes([r, g, b, a])
+}
+
+/// 将 `0xRRGGBBAA` 拆分为 (r, g, b, a)
+#[inline]
+pub fn unpack_rgba(color: u32) -> (u8, u8, u8, u8) {
+    let [r, g, b, a] = color.to_be_bytes();
+    (r, g, b, a)
+}
+
+/// 打包不透明颜色（alpha = 255）
+///
+/// 注意 `0xRRGGBB` 形式的字面量会被当作 `0x00RRGGBB`，即 alpha 为 0 的全透明颜色，
+/// 只有 RGB 时应使用本函数
+#[inline]
+pub fn rgb(r: u8, g: u8, b: u8) -> u32 {
+    pack_rgba(r, g, b, 255)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rgb_is_opaque() {
+        assert_eq!(rgb(255, 0, 0), 0xFF0000FF);
+        assert_eq!(rgb(0x12, 0x34, 0x56), 0x123456FF);
+    }
+
+    #[test]
+    fn test_pack_unpack_roundtrip() {
+        assert_eq!(pack_rgba(1, 2, 3, 4), 0x01020304);
+        assert_eq!(unpack_rgba(0x01020304), (1, 2, 3, 4));
+        // 漏写 alpha 的 0xRRGGBB 会得到全透明颜色
+        assert_eq!(unpack_rgba(0xFF0000), (0, 0xFF, 0, 0));
+    }
+
+    #[test]
+    fn test_default_is_rec601() {
+        assert_eq!(LuminanceWeights::default(), LuminanceWeights::Rec601);
+    }
+
+    #[test]
+    fn test_green_differs_between_standards() {
+        let rec601 = LuminanceWeights::Rec601.luminance(0, 255, 0);
+        let rec709 = LuminanceWeights::Rec709.luminance(0, 255, 0);
+        assert_eq!(rec601, 150);
+        assert_eq!(rec709, 182);
+        assert_ne!(rec601, rec709);
+    }
+
+    #[test]
+    fn test_white_stays_white() {
+        for weights in [
+            LuminanceWeights::Rec601,
+            LuminanceWeights::Rec709,
+            LuminanceWeights::Average,
+        ] {
+            assert_eq!(weights.luminance(255, 255, 255), 255);
+        }
+    }
+}
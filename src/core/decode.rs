@@ -0,0 +1,170 @@
+//! 图像解码
+//!
+//! 将 PNG/JPEG 编码数据解码为本库使用的像素格式
+
+use std::fmt;
+
+use super::format::ImageFormat;
+
+/// 图像解码错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    /// 无法识别的图像格式（目前只支持 PNG 和 JPEG）
+    UnsupportedFormat,
+    /// 数据损坏或内容不受支持，附带解码器给出的原因
+    Corrupt(String),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnsupportedFormat => write!(f, "unsupported image format"),
+            DecodeError::Corrupt(reason) => write!(f, "corrupt image data: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// 解码结果：(像素数据, 宽度, 高度, 格式)
+pub(crate) type Decoded = (Vec<u8>, u32, u32, ImageFormat);
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_SIGNATURE: &[u8] = &[0xFF, 0xD8, 0xFF];
+
+/// 根据文件头识别格式并解码
+///
+/// 灰度、RGB、RGBA 保持原格式；带 alpha 的灰度图展开为 RGBA，
+/// 调色板图展开为 RGB/RGBA，16 位数据截断为 8 位
+pub(crate) fn decode(bytes: &[u8]) -> Result<Decoded, DecodeError> {
+    if bytes.starts_with(PNG_SIGNATURE) {
+        decode_png(bytes)
+    } else if bytes.starts_with(JPEG_SIGNATURE) {
+        decode_jpeg(bytes)
+    } else {
+        Err(DecodeError::UnsupportedFormat)
+    }
+}
+
+fn decode_png(bytes: &[u8]) -> Result<Decoded, DecodeError> {
+    let corrupt = |e: png::DecodingError| DecodeError::Corrupt(e.to_string());
+
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(corrupt)?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(corrupt)?;
+    buffer.truncate(info.buffer_size());
+
+    let format = match info.color_type {
+        png::ColorType::Grayscale => ImageFormat::Grayscale,
+        png::ColorType::Rgb => ImageFormat::Rgb,
+        png::ColorType::Rgba => ImageFormat::Rgba,
+        png::ColorType::GrayscaleAlpha => {
+            buffer = buffer
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect();
+            ImageFormat::Rgba
+        }
+        png::ColorType::Indexed => {
+            return Err(DecodeError::Corrupt("unexpanded palette".to_string()));
+        }
+    };
+    Ok((buffer, info.width, info.height, format))
+}
+
+fn decode_jpeg(bytes: &[u8]) -> Result<Decoded, DecodeError> {
+    let mut decoder = jpeg_decoder::Decoder::new(bytes);
+    let pixels = decoder
+        .decode()
+        .map_err(|e| DecodeError::Corrupt(e.to_string()))?;
+    let info = decoder
+        .info()
+        .ok_or_else(|| DecodeError::Corrupt("missing image info".to_string()))?;
+
+    let (buffer, format) = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => (pixels, ImageFormat::Grayscale),
+        // 16 位灰度为大端序，取高字节
+        jpeg_decoder::PixelFormat::L16 => (
+            pixels.chunks_exact(2).map(|v| v[0]).collect(),
+            ImageFormat::Grayscale,
+        ),
+        jpeg_decoder::PixelFormat::RGB24 => (pixels, ImageFormat::Rgb),
+        jpeg_decoder::PixelFormat::CMYK32 => (
+            pixels
+                .chunks_exact(4)
+                .flat_map(|cmyk| {
+                    let k = cmyk[3] as u32;
+                    [0, 1, 2].map(|c| (cmyk[c] as u32 * k / 255) as u8)
+                })
+                .collect(),
+            ImageFormat::Rgb,
+        ),
+    };
+    Ok((buffer, info.width as u32, info.height as u32, format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 用 png crate 编码测试图像
+    fn encode_png(pixels: &[u8], width: u32, height: u32, color: png::ColorType) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(pixels).unwrap();
+        drop(writer);
+        bytes
+    }
+
+    #[test]
+    fn test_decode_png_keeps_format() {
+        let bytes = encode_png(&[1, 2, 3, 4, 5, 6], 2, 1, png::ColorType::Rgb);
+        let (pixels, width, height, format) = decode(&bytes).unwrap();
+        assert_eq!((width, height, format), (2, 1, ImageFormat::Rgb));
+        assert_eq!(pixels, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_decode_png_gray_alpha_expands_to_rgba() {
+        let bytes = encode_png(&[100, 200], 1, 1, png::ColorType::GrayscaleAlpha);
+        let (pixels, _, _, format) = decode(&bytes).unwrap();
+        assert_eq!(format, ImageFormat::Rgba);
+        assert_eq!(pixels, vec![100, 100, 100, 200]);
+    }
+
+    #[test]
+    fn test_decode_jpeg() {
+        let mut bytes = Vec::new();
+        let encoder = jpeg_encoder::Encoder::new(&mut bytes, 100);
+        encoder
+            .encode(&[128; 4 * 2 * 3], 4, 2, jpeg_encoder::ColorType::Rgb)
+            .unwrap();
+
+        let (pixels, width, height, format) = decode(&bytes).unwrap();
+        assert_eq!((width, height, format), (4, 2, ImageFormat::Rgb));
+        // 有损压缩，允许少量误差
+        assert!(pixels.iter().all(|&v| v.abs_diff(128) <= 2));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode(b"GIF89a...."), Err(DecodeError::UnsupportedFormat));
+        assert_eq!(decode(&[]), Err(DecodeError::UnsupportedFormat));
+
+        // 文件头正确但内容被截断
+        let bytes = encode_png(&[1, 2, 3], 1, 1, png::ColorType::Rgb);
+        assert!(matches!(
+            decode(&bytes[..bytes.len() / 2]),
+            Err(DecodeError::Corrupt(_))
+        ));
+        assert!(matches!(
+            decode(&[0xFF, 0xD8, 0xFF, 0x00]),
+            Err(DecodeError::Corrupt(_))
+        ));
+    }
+}
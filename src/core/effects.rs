@@ -0,0 +1,759 @@
+//! 图像效果
+//!
+//! SharedBuffer 上的滤镜与效果操作（模糊、颜色矩阵等）
+
+use wasm_bindgen::prelude::*;
+
+use super::buffer::SharedBuffer;
+use super::color::{LuminanceWeights, pack_rgba, unpack_rgba};
+use super::format::ImageFormat;
+
+#[wasm_bindgen]
+impl SharedBuffer {
+    /// 盒式模糊
+    ///
+    /// 水平、垂直两遍可分离滑动窗口，复杂度 O(n) 与半径无关，边缘像素钳制。
+    /// 启用 `parallel` feature 时按行并行处理。
+    ///
+    /// # Arguments
+    /// * `radius` - 模糊半径（窗口大小为 `2 * radius + 1`，超过图像长边时钳制）
+    pub fn box_blur(&mut self, radius: u32) {
+        self.box_blur_with(radius, cfg!(feature = "parallel"));
+    }
+
+    /// 反色（每个颜色通道取 `255 - v`），RGBA 的 alpha 保持不变
+    pub fn invert(&mut self) {
+        let bpp = self.format.bytes_per_pixel();
+        let color_channels = if self.format == ImageFormat::Rgba {
+            3
+        } else {
+            bpp
+        };
+        for pixel in self.buffer.chunks_exact_mut(bpp) {
+            for v in &mut pixel[..color_channels] {
+                *v = 255 - *v;
+            }
+        }
+    }
+
+    /// 原地去色：按 Rec. 601 亮度写回 RGB 三个通道，格式不变
+    ///
+    /// 灰度格式不做处理，RGBA 的 alpha 保持不变
+    pub fn to_grayscale(&mut self) {
+        self.to_grayscale_with_weights(LuminanceWeights::default());
+    }
+
+    /// 原地去色，使用指定的亮度权重（如高清内容使用 Rec. 709）
+    pub fn to_grayscale_with_weights(&mut self, weights: LuminanceWeights) {
+        if self.format == ImageFormat::Grayscale {
+            return;
+        }
+        let bpp = self.format.bytes_per_pixel();
+        for pixel in self.buffer.chunks_exact_mut(bpp) {
+            let l = weights.luminance(pixel[0], pixel[1], pixel[2]);
+            pixel[..3].fill(l);
+        }
+    }
+
+    /// 高斯模糊
+    ///
+    /// 使用半径约为 `3 * sigma`（不超过图像长边）的归一化一维高斯核，
+    /// 水平、垂直两遍可分离卷积，边缘像素钳制。
+    /// 启用 `parallel` feature 时按行并行处理。
+    ///
+    /// # Arguments
+    /// * `sigma` - 高斯标准差（像素），`<= 0` 或非有限值时不做处理
+    pub fn gaussian_blur(&mut self, sigma: f32) {
+        if !(sigma > 0.0 && sigma.is_finite()) || self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        let bpp = self.format as usize;
+        let (width, height) = (self.width as usize, self.height as usize);
+        let kernel = gaussian_kernel(sigma, width.max(height));
+        let parallel = cfg!(feature = "parallel");
+
+        for_each_row(&mut self.buffer, width * bpp, parallel, |row| {
+            convolve_row(row, bpp, &kernel)
+        });
+
+        let mut transposed = transpose(&self.buffer, width, height, bpp);
+        for_each_row(&mut transposed, height * bpp, parallel, |row| {
+            convolve_row(row, bpp, &kernel)
+        });
+        self.buffer = transpose(&transposed, height, width, bpp);
+    }
+
+    /// 通用卷积
+    ///
+    /// 对颜色通道应用任意 `kw x kh` 卷积核（锐化、浮雕、边缘检测等），
+    /// 结果为 `sum / divisor + bias`，边缘像素钳制，输出钳制到 0-255。
+    /// RGBA 的 alpha 保持不变。
+    ///
+    /// # Arguments
+    /// * `kernel` - 卷积核，行主序，长度必须为 `kw * kh`
+    /// * `kw` / `kh` - 卷积核宽高，中心为 `(kw / 2, kh / 2)`
+    /// * `divisor` - 除数，不能为 0
+    /// * `bias` - 偏移（0-255 为单位）
+    pub fn convolve(
+        &mut self,
+        kernel: &[f32],
+        kw: u32,
+        kh: u32,
+        divisor: f32,
+        bias: f32,
+    ) -> Result<(), String> {
+        let expected = (kw as usize).checked_mul(kh as usize);
+        if kw == 0 || kh == 0 || expected != Some(kernel.len()) {
+            return Err(format!(
+                "kernel length {} does not match {}x{}",
+                kernel.len(),
+                kw,
+                kh
+            ));
+        }
+        if divisor == 0.0 {
+            return Err("divisor must not be zero".to_string());
+        }
+        if self.width == 0 || self.height == 0 {
+            return Ok(());
+        }
+
+        let bpp = self.format as usize;
+        let color_channels = if self.format == ImageFormat::Rgba {
+            3
+        } else {
+            bpp
+        };
+        let (width, height) = (self.width as i64, self.height as i64);
+        let (cx, cy) = ((kw / 2) as i64, (kh / 2) as i64);
+
+        let src = self.buffer.clone();
+        for y in 0..height {
+            for x in 0..width {
+                let dst = ((y * width + x) as usize) * bpp;
+                for c in 0..color_channels {
+                    let mut sum = 0.0;
+                    for ky in 0..kh as i64 {
+                        let sy = (y + ky - cy).clamp(0, height - 1);
+                        for kx in 0..kw as i64 {
+                            let sx = (x + kx - cx).clamp(0, width - 1);
+                            let weight = kernel[(ky * kw as i64 + kx) as usize];
+                            sum += src[((sy * width + sx) as usize) * bpp + c] as f32 * weight;
+                        }
+                    }
+                    self.buffer[dst + c] = (sum / divisor + bias).round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 运动模糊
+    ///
+    /// 对每个像素沿给定方向、以该像素为中心的 `length` 个采样点取平均，
+    /// 超出边缘的采样点钳制到边缘像素。RGBA 格式的 alpha 通道保持不变。
+    ///
+    /// # Arguments
+    /// * `angle_degrees` - 模糊方向（角度，0 为水平向右，顺时针为正）
+    /// * `length` - 采样线长度（像素），`<= 1` 时不做处理，超过图像对角线时钳制
+    pub fn motion_blur(&mut self, angle_degrees: f32, length: u32) {
+        if length <= 1 || self.width == 0 || self.height == 0 {
+            return;
+        }
+        let diagonal = (self.width as f64).hypot(self.height as f64).ceil() as u32;
+        let length = length.min(diagonal);
+
+        let bpp = self.format as usize;
+        // RGBA 只模糊颜色通道
+        let color_channels = if self.format == ImageFormat::Rgba {
+            3
+        } else {
+            bpp
+        };
+        let (width, height) = (self.width as i64, self.height as i64);
+        let (sin, cos) = angle_degrees.to_radians().sin_cos();
+        let half = (length - 1) as f32 / 2.0;
+
+        // 预先计算采样偏移，所有像素共用
+        let offsets: Vec<(i64, i64)> = (0..length)
+            .map(|i| {
+                let t = i as f32 - half;
+                ((t * cos).round() as i64, (t * sin).round() as i64)
+            })
+            .collect();
+
+        let samples = length as u64;
+        let src = self.buffer.clone();
+        for y in 0..height {
+            for x in 0..width {
+                let dst = ((y * width + x) as usize) * bpp;
+                for c in 0..color_channels {
+                    let sum: u64 = offsets
+                        .iter()
+                        .map(|&(dx, dy)| {
+                            let sx = (x + dx).clamp(0, width - 1);
+                            let sy = (y + dy).clamp(0, height - 1);
+                            src[((sy * width + sx) as usize) * bpp + c] as u64
+                        })
+                        .sum();
+                    self.buffer[dst + c] = ((sum + samples / 2) / samples) as u8;
+                }
+            }
+        }
+    }
+
+    /// 径向渐变
+    ///
+    /// 按像素中心到圆心的距离与 `radius` 之比在两种颜色（`0xRRGGBBAA`）间插值，
+    /// 超出半径的部分为 `outer_color`。按 buffer 格式写入，规则同 `fill_color`。
+    ///
+    /// # Arguments
+    /// * `center_x` / `center_y` - 圆心（像素坐标）
+    /// * `radius` - 渐变半径，`<= 0` 时整个 buffer 为 `outer_color`
+    /// * `inner_color` - 圆心颜色
+    /// * `outer_color` - 半径处及以外的颜色
+    pub fn radial_gradient(
+        &mut self,
+        center_x: f32,
+        center_y: f32,
+        radius: f32,
+        inner_color: u32,
+        outer_color: u32,
+    ) {
+        self.fill_with(|x, y| {
+            let t = if radius > 0.0 {
+                let dx = x as f32 + 0.5 - center_x;
+                let dy = y as f32 + 0.5 - center_y;
+                (dx * dx + dy * dy).sqrt() / radius
+            } else {
+                1.0
+            };
+            lerp_color(inner_color, outer_color, t)
+        });
+    }
+
+    /// 任意方向的线性渐变
+    ///
+    /// 将像素中心投影到方向向量上，按投影在整个 buffer 范围内的位置插值，
+    /// 45° 即从左上角到右下角的对角渐变。按 buffer 格式写入，规则同 `fill_color`。
+    ///
+    /// # Arguments
+    /// * `angle_degrees` - 渐变方向（角度，0 为水平向右，顺时针为正）
+    /// * `start_color` - 起点颜色
+    /// * `end_color` - 终点颜色
+    pub fn linear_gradient(&mut self, angle_degrees: f32, start_color: u32, end_color: u32) {
+        let (sin, cos) = angle_degrees.to_radians().sin_cos();
+        let project = |x: f32, y: f32| x * cos + y * sin;
+
+        // 投影范围由四个角决定
+        let (w, h) = (self.width as f32, self.height as f32);
+        let corners = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)].map(|(x, y)| project(x, y));
+        let min = corners.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = corners.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let span = max - min;
+
+        self.fill_with(|x, y| {
+            let t = if span > 0.0 {
+                (project(x as f32 + 0.5, y as f32 + 0.5) - min) / span
+            } else {
+                0.0
+            };
+            lerp_color(start_color, end_color, t)
+        });
+    }
+}
+
+impl SharedBuffer {
+    /// 应用 4x5 颜色矩阵
+    ///
+    /// 按行主序解释为 4 行（输出 R、G、B、A）x 5 列（输入 R、G、B、A、偏移），
+    /// 偏移列以 0-255 为单位，结果钳制到 0-255。
+    /// 棕褐色、去色、色相旋转、通道交换等都可以表示为颜色矩阵。
+    ///
+    /// 非 RGBA 格式按不透明像素处理：RGB 丢弃输出 alpha，
+    /// 灰度图取输出颜色的亮度（Rec.601）。
+    pub fn apply_color_matrix(&mut self, m: &[f32; 20]) {
+        let bpp = self.format as usize;
+        let format = self.format;
+
+        for pixel in self.buffer.chunks_exact_mut(bpp) {
+            let input = match format {
+                ImageFormat::Rgba => [pixel[0], pixel[1], pixel[2], pixel[3]],
+                ImageFormat::Rgb => [pixel[0], pixel[1], pixel[2], 255],
+                ImageFormat::Grayscale => [pixel[0], pixel[0], pixel[0], 255],
+            }
+            .map(|v| v as f32);
+
+            let mut output = [0u8; 4];
+            for (row, out) in output.iter_mut().enumerate() {
+                let coeffs = &m[row * 5..row * 5 + 5];
+                let value = coeffs[0] * input[0]
+                    + coeffs[1] * input[1]
+                    + coeffs[2] * input[2]
+                    + coeffs[3] * input[3]
+                    + coeffs[4];
+                *out = value.round().clamp(0.0, 255.0) as u8;
+            }
+
+            match format {
+                ImageFormat::Grayscale => {
+                    pixel[0] =
+                        LuminanceWeights::default().luminance(output[0], output[1], output[2])
+                }
+                _ => pixel.copy_from_slice(&output[..bpp]),
+            }
+        }
+    }
+
+    /// 按坐标计算每个像素的颜色（`0xRRGGBBAA`）并按 buffer 格式写入
+    fn fill_with(&mut self, color_at: impl Fn(u32, u32) -> u32) {
+        let bpp = self.format.bytes_per_pixel();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pixel = self.native_color(color_at(x, y));
+                let idx = (y as usize * self.width as usize + x as usize) * bpp;
+                self.buffer[idx..idx + bpp].copy_from_slice(&pixel[..bpp]);
+            }
+        }
+    }
+
+    /// 盒式模糊的实现，`parallel` 为 true 且启用 `parallel` feature 时并行执行
+    fn box_blur_with(&mut self, radius: u32, parallel: bool) {
+        if radius == 0 || self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        let bpp = self.format as usize;
+        let (width, height) = (self.width as usize, self.height as usize);
+        // 半径超过图像长边后只会重复采样边缘像素，钳制以限制循环次数
+        let radius = (radius as usize).min(width.max(height));
+
+        // 水平方向：逐行处理
+        for_each_row(&mut self.buffer, width * bpp, parallel, |row| {
+            blur_row(row, bpp, radius)
+        });
+
+        // 垂直方向：转置后按行处理，再转置回来
+        let mut transposed = transpose(&self.buffer, width, height, bpp);
+        for_each_row(&mut transposed, height * bpp, parallel, |row| {
+            blur_row(row, bpp, radius)
+        });
+        self.buffer = transpose(&transposed, height, width, bpp);
+    }
+}
+
+/// 对每一行执行操作，启用 `parallel` feature 时可使用 rayon 并行
+fn for_each_row<F>(data: &mut [u8], row_len: usize, parallel: bool, f: F)
+where
+    F: Fn(&mut [u8]) + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    if parallel {
+        use rayon::prelude::*;
+        data.par_chunks_mut(row_len).for_each(f);
+        return;
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    let _ = parallel;
+
+    data.chunks_mut(row_len).for_each(f);
+}
+
+/// 在两种 `0xRRGGBBAA` 颜色间逐通道线性插值，`t` 钳制到 0-1
+fn lerp_color(from: u32, to: u32, t: f32) -> u32 {
+    let t = t.clamp(0.0, 1.0);
+    let (r0, g0, b0, a0) = unpack_rgba(from);
+    let (r1, g1, b1, a1) = unpack_rgba(to);
+    let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    pack_rgba(lerp(r0, r1), lerp(g0, g1), lerp(b0, b1), lerp(a0, a1))
+}
+
+/// 构建归一化的一维高斯核，长度为 `2 * min(ceil(3 * sigma), max_radius) + 1`
+fn gaussian_kernel(sigma: f32, max_radius: usize) -> Vec<f32> {
+    let radius = ((3.0 * sigma).ceil() as usize).min(max_radius) as i64;
+    let denom = 2.0 * sigma * sigma;
+    let mut kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| (-(i as f32 * i as f32) / denom).exp())
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|w| *w /= sum);
+    kernel
+}
+
+/// 对单行像素做一维卷积（核长度为奇数，以中心对齐，边缘钳制）
+fn convolve_row(row: &mut [u8], bpp: usize, kernel: &[f32]) {
+    let len = row.len() / bpp;
+    let src = row.to_vec();
+    let radius = (kernel.len() / 2) as isize;
+
+    for x in 0..len {
+        for c in 0..bpp {
+            let value: f32 = kernel
+                .iter()
+                .enumerate()
+                .map(|(k, w)| {
+                    let i = (x as isize + k as isize - radius).clamp(0, len as isize - 1);
+                    src[i as usize * bpp + c] as f32 * w
+                })
+                .sum();
+            row[x * bpp + c] = value.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// 对单行像素做一维盒式模糊（滑动窗口求和，边缘钳制）
+fn blur_row(row: &mut [u8], bpp: usize, radius: usize) {
+    let len = row.len() / bpp;
+    let src = row.to_vec();
+    let window = 2 * radius as u64 + 1;
+    let at = |i: isize, c: usize| src[(i.clamp(0, len as isize - 1) as usize) * bpp + c] as u64;
+
+    for c in 0..bpp {
+        let r = radius as isize;
+        let mut sum: u64 = (-r..=r).map(|i| at(i, c)).sum();
+
+        for x in 0..len {
+            row[x * bpp + c] = ((sum + window / 2) / window) as u8;
+            let x = x as isize;
+            sum = sum + at(x + r + 1, c) - at(x - r, c);
+        }
+    }
+}
+
+/// 转置像素矩阵（`width x height` -> `height x width`）
+fn transpose(data: &[u8], width: usize, height: usize, bpp: usize) -> Vec<u8> {
+    let mut out = vec![0; data.len()];
+    for y in 0..height {
+        for x in 0..width {
+            let src = (y * width + x) * bpp;
+            let dst = (x * height + y) * bpp;
+            out[dst..dst + bpp].copy_from_slice(&data[src..src + bpp]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    const IDENTITY: [f32; 20] = [
+        1.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 0.0, 1.0, 0.0,
+    ];
+
+    #[test]
+    fn test_color_matrix_identity_unchanged() {
+        let mut buffer = SharedBuffer::new(4, 3, ImageFormat::Rgba);
+        for (i, byte) in buffer.buffer.iter_mut().enumerate() {
+            *byte = (i * 13) as u8;
+        }
+        let original = buffer.buffer.clone();
+        buffer.apply_color_matrix(&IDENTITY);
+        assert_eq!(buffer.buffer, original);
+    }
+
+    #[test]
+    fn test_color_matrix_grayscale_desaturates() {
+        #[rustfmt::skip]
+        let grayscale = [
+            0.299, 0.587, 0.114, 0.0, 0.0,
+            0.299, 0.587, 0.114, 0.0, 0.0,
+            0.299, 0.587, 0.114, 0.0, 0.0,
+            0.0,   0.0,   0.0,   1.0, 0.0,
+        ];
+        let mut buffer = SharedBuffer::new(1, 1, ImageFormat::Rgba);
+        buffer.buffer.copy_from_slice(&[255, 0, 0, 200]);
+        buffer.apply_color_matrix(&grayscale);
+
+        // 纯红 -> 0.299 * 255 ≈ 76，alpha 保持不变
+        assert_eq!(buffer.buffer, vec![76, 76, 76, 200]);
+    }
+
+    #[test]
+    fn test_color_matrix_bias_clamps() {
+        let mut matrix = IDENTITY;
+        matrix[4] = 100.0; // R 偏移
+        let mut buffer = SharedBuffer::new(1, 1, ImageFormat::Rgb);
+        buffer.buffer.copy_from_slice(&[200, 10, 20]);
+        buffer.apply_color_matrix(&matrix);
+        assert_eq!(buffer.buffer, vec![255, 10, 20]);
+    }
+
+    #[test]
+    fn test_radial_gradient() {
+        let mut buffer = SharedBuffer::new(11, 11, ImageFormat::Rgba);
+        buffer.radial_gradient(5.5, 5.5, 4.0, 0xFFFFFFFF, 0x000000FF);
+
+        assert_eq!(buffer.get_pixel(5, 5), 0xFFFFFFFF);
+        // 距离 2，插值一半
+        assert_eq!(buffer.get_pixel(7, 5), 0x808080FF);
+        // 半径之外钳制为外圈颜色
+        assert_eq!(buffer.get_pixel(0, 0), 0x000000FF);
+        assert_eq!(buffer.get_pixel(10, 5), 0x000000FF);
+
+        let mut gray = SharedBuffer::new(4, 1, ImageFormat::Grayscale);
+        gray.radial_gradient(0.5, 0.5, 2.0, 0xC8C8C8FF, 0x000000FF);
+        assert_eq!(gray.into_vec(), vec![200, 100, 0, 0]);
+
+        let mut rgb = SharedBuffer::new(2, 1, ImageFormat::Rgb);
+        rgb.radial_gradient(0.0, 0.0, 0.0, 0xFFFFFFFF, 0x102030FF);
+        assert_eq!(rgb.into_vec(), vec![0x10, 0x20, 0x30, 0x10, 0x20, 0x30]);
+    }
+
+    #[test]
+    fn test_linear_gradient_direction() {
+        let mut buffer = SharedBuffer::new(4, 3, ImageFormat::Rgba);
+        buffer.linear_gradient(0.0, 0x000000FF, 0xFFFFFFFF);
+
+        // 0° 只沿 x 方向变化，从左到右递增
+        for y in 0..3 {
+            let row: Vec<u32> = (0..4).map(|x| buffer.get_pixel(x, y)).collect();
+            assert_eq!(
+                row,
+                (0..4).map(|x| buffer.get_pixel(x, 0)).collect::<Vec<_>>()
+            );
+        }
+        assert!((0..3).all(|x| buffer.get_pixel(x, 0) < buffer.get_pixel(x + 1, 0)));
+        assert_eq!(buffer.get_pixel(0, 0), 0x202020FF);
+
+        // 90° 只沿 y 方向变化
+        let mut vertical = SharedBuffer::new(3, 4, ImageFormat::Grayscale);
+        vertical.linear_gradient(90.0, 0x000000FF, 0xFFFFFFFF);
+        let data = vertical.into_vec();
+        for row in data.chunks_exact(3) {
+            assert!(row.iter().all(|&v| v == row[0]));
+        }
+        assert!(data[0] < data[9]);
+
+        // 45° 为对角渐变，两个对角的中间点颜色相同
+        let mut diagonal = SharedBuffer::new(4, 4, ImageFormat::Rgb);
+        diagonal.linear_gradient(45.0, 0x000000FF, 0xFFFFFFFF);
+        assert_eq!(diagonal.get_pixel(3, 0), diagonal.get_pixel(0, 3));
+        assert!(diagonal.get_pixel(0, 0) < diagonal.get_pixel(3, 3));
+    }
+
+    #[test]
+    fn test_gaussian_kernel_normalized() {
+        for sigma in [0.5, 1.0, 2.5] {
+            let kernel = gaussian_kernel(sigma, usize::MAX);
+            assert_eq!(kernel.len() % 2, 1);
+            assert!((kernel.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+            // 对称且中心权重最大
+            let mid = kernel.len() / 2;
+            assert!((kernel[0] - kernel[kernel.len() - 1]).abs() < 1e-7);
+            assert!(kernel.iter().all(|&w| w <= kernel[mid]));
+        }
+
+        // 半径不超过上限
+        assert_eq!(gaussian_kernel(1000.0, 4).len(), 9);
+    }
+
+    #[test]
+    fn test_gaussian_blur() {
+        let mut flat = SharedBuffer::new(6, 5, ImageFormat::Rgba);
+        flat.fill_color(0x336699CC);
+        flat.gaussian_blur(1.5);
+        assert!((0..5).all(|y| (0..6).all(|x| flat.get_pixel(x, y) == 0x336699CC)));
+
+        // 单个亮点被扩散，总能量大致守恒
+        let mut spot = SharedBuffer::new(9, 9, ImageFormat::Grayscale);
+        spot.set_pixel(4, 4, 0xFFFFFFFF);
+        spot.gaussian_blur(1.0);
+        let data = spot.into_vec();
+        assert!(data[4 * 9 + 4] < 255 && data[4 * 9 + 5] > 0);
+        let total: u32 = data.iter().map(|&v| v as u32).sum();
+        assert!(total.abs_diff(255) < 20);
+    }
+
+    #[test]
+    fn test_invert() {
+        let mut rgba = SharedBuffer::new(1, 1, ImageFormat::Rgba);
+        rgba.fill_color(0x10C8FF80);
+        rgba.invert();
+        assert_eq!(rgba.get_pixel(0, 0), 0xEF370080);
+
+        let mut gray = SharedBuffer::new(2, 1, ImageFormat::Grayscale);
+        gray.fill_color(0x404040FF);
+        gray.invert();
+        assert_eq!(gray.into_vec(), vec![0xBF, 0xBF]);
+    }
+
+    #[test]
+    fn test_to_grayscale() {
+        // 0.299 * 200 + 0.587 * 100 + 0.114 * 50 = 124.2
+        let mut rgba = SharedBuffer::new(1, 1, ImageFormat::Rgba);
+        rgba.fill_color(pack_rgba(200, 100, 50, 77));
+        rgba.to_grayscale();
+        assert_eq!(rgba.get_pixel(0, 0), pack_rgba(124, 124, 124, 77));
+
+        let mut rgb = SharedBuffer::new(1, 1, ImageFormat::Rgb);
+        rgb.fill_color(pack_rgba(200, 100, 50, 255));
+        rgb.to_grayscale();
+        assert_eq!(rgb.into_vec(), vec![124, 124, 124]);
+
+        let mut gray = SharedBuffer::new(1, 1, ImageFormat::Grayscale);
+        gray.set_pixel(0, 0, 0x909090FF);
+        gray.to_grayscale();
+        assert_eq!(gray.into_vec(), vec![0x90]);
+    }
+
+    #[test]
+    fn test_to_grayscale_with_weights() {
+        let gray_of_green = |weights| {
+            let mut buffer = SharedBuffer::new(1, 1, ImageFormat::Rgb);
+            buffer.fill_color(pack_rgba(0, 255, 0, 255));
+            buffer.to_grayscale_with_weights(weights);
+            buffer.into_vec()[0]
+        };
+        // 0.587 * 255 = 149.7，0.7152 * 255 = 182.4
+        assert_eq!(gray_of_green(LuminanceWeights::Rec601), 150);
+        assert_eq!(gray_of_green(LuminanceWeights::Rec709), 182);
+    }
+
+    #[test]
+    fn test_convolve_sharpen() {
+        #[rustfmt::skip]
+        let sharpen = [
+            0.0, -1.0, 0.0,
+            -1.0, 5.0, -1.0,
+            0.0, -1.0, 0.0,
+        ];
+        #[rustfmt::skip]
+        let data = vec![
+            10, 20, 30,
+            40, 50, 60,
+            70, 80, 90,
+        ];
+        let mut buffer = SharedBuffer::from_vec(3, 3, ImageFormat::Grayscale, data).unwrap();
+        buffer.convolve(&sharpen, 3, 3, 1.0, 0.0).unwrap();
+
+        // 中心：5 * 50 - (20 + 40 + 60 + 80) = 50
+        let out = buffer.into_vec();
+        assert_eq!(out[4], 50);
+        // 左上角（边缘钳制）：5 * 10 - (10 + 10 + 20 + 40) = -30 -> 0
+        assert_eq!(out[0], 0);
+        // 右下角：5 * 90 - (60 + 80 + 90 + 90) = 130
+        assert_eq!(out[8], 130);
+    }
+
+    #[test]
+    fn test_convolve_rejects_bad_kernel() {
+        let mut buffer = SharedBuffer::new(2, 2, ImageFormat::Rgba);
+        buffer.fill_color(0x102030FF);
+        assert!(buffer.convolve(&[1.0; 8], 3, 3, 1.0, 0.0).is_err());
+        assert!(buffer.convolve(&[1.0; 9], 3, 3, 0.0, 0.0).is_err());
+        // 核尺寸乘积溢出 u32 时返回错误而非 panic
+        assert!(buffer.convolve(&[1.0; 9], 65536, 65536, 1.0, 0.0).is_err());
+        assert!(buffer.convolve(&[1.0; 9], u32::MAX, 3, 1.0, 0.0).is_err());
+
+        // 偏移作用于颜色通道，alpha 不变
+        buffer.convolve(&[1.0], 1, 1, 1.0, 16.0).unwrap();
+        assert_eq!(buffer.get_pixel(1, 1), 0x203040FF);
+    }
+
+    #[test]
+    fn test_gaussian_blur_huge_sigma() {
+        // 超大 sigma 不会溢出或分配巨大的卷积核，结果接近均值
+        let mut buffer = SharedBuffer::new(2, 2, ImageFormat::Grayscale);
+        buffer.buffer.copy_from_slice(&[0, 100, 100, 200]);
+        buffer.gaussian_blur(20000.0);
+        buffer.gaussian_blur(f32::MAX);
+        assert!(buffer.buffer.iter().all(|&v| (90..=110).contains(&v)));
+    }
+
+    #[test]
+    fn test_box_blur_flat_image_unchanged() {
+        let mut buffer = SharedBuffer::new(8, 6, ImageFormat::Rgba);
+        buffer.buffer.fill(123);
+        buffer.box_blur(2);
+        assert!(buffer.buffer.iter().all(|&v| v == 123));
+    }
+
+    #[test]
+    fn test_box_blur_spreads_bright_pixel() {
+        let mut buffer = SharedBuffer::new(5, 5, ImageFormat::Grayscale);
+        buffer.buffer[2 * 5 + 2] = 225;
+        buffer.box_blur(1);
+
+        // 3x3 窗口均分能量
+        assert_eq!(buffer.buffer[2 * 5 + 2], 25);
+        assert_eq!(buffer.buffer[5 + 1], 25);
+        assert_eq!(buffer.buffer[0], 0);
+    }
+
+    #[test]
+    fn test_box_blur_huge_radius() {
+        // 超大半径不会溢出，结果接近均值
+        let mut buffer = SharedBuffer::new(2, 1, ImageFormat::Grayscale);
+        buffer.buffer.copy_from_slice(&[0, 200]);
+        buffer.box_blur(20_000_000);
+        assert!(buffer.buffer.iter().all(|&v| (60..=140).contains(&v)));
+        buffer.box_blur(u32::MAX);
+    }
+
+    #[test]
+    fn test_box_blur_zero_radius_noop() {
+        let mut buffer = SharedBuffer::new(3, 3, ImageFormat::Rgb);
+        for (i, byte) in buffer.buffer.iter_mut().enumerate() {
+            *byte = (i * 7) as u8;
+        }
+        let original = buffer.buffer.clone();
+        buffer.box_blur(0);
+        assert_eq!(buffer.buffer, original);
+    }
+
+    #[test]
+    fn test_motion_blur_huge_length() {
+        // 长度钳制到对角线，不会分配海量采样点
+        let data = vec![0, 30, 60, 90, 120, 150];
+        let mut buffer =
+            SharedBuffer::from_vec(3, 2, ImageFormat::Grayscale, data.clone()).unwrap();
+        let mut clamped = SharedBuffer::from_vec(3, 2, ImageFormat::Grayscale, data).unwrap();
+        buffer.motion_blur(0.0, u32::MAX);
+        clamped.motion_blur(0.0, 4);
+        assert_eq!(buffer.buffer, clamped.buffer);
+    }
+
+    #[test]
+    fn test_motion_blur_horizontal_smear() {
+        let mut buffer = SharedBuffer::new(9, 5, ImageFormat::Rgba);
+        let idx = |x: usize, y: usize| (y * 9 + x) * 4;
+        buffer.buffer[idx(4, 2)..idx(4, 2) + 4].copy_from_slice(&[250, 250, 250, 255]);
+
+        buffer.motion_blur(0.0, 5);
+
+        // 沿 x 轴拖影，能量均分到 5 个像素
+        for x in 2..=6 {
+            assert_eq!(buffer.buffer[idx(x, 2)], 50);
+        }
+        assert_eq!(buffer.buffer[idx(1, 2)], 0);
+        assert_eq!(buffer.buffer[idx(7, 2)], 0);
+        // y 方向没有扩散
+        assert_eq!(buffer.buffer[idx(4, 1)], 0);
+        assert_eq!(buffer.buffer[idx(4, 3)], 0);
+        // alpha 保持不变
+        assert_eq!(buffer.buffer[idx(4, 2) + 3], 255);
+        assert_eq!(buffer.buffer[idx(3, 2) + 3], 0);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_blur_matches_serial() {
+        let mut serial = SharedBuffer::new(97, 61, ImageFormat::Rgba);
+        for (i, byte) in serial.buffer.iter_mut().enumerate() {
+            *byte = (i.wrapping_mul(2654435761) >> 7) as u8;
+        }
+        let mut parallel = SharedBuffer::new(97, 61, ImageFormat::Rgba);
+        parallel.buffer.copy_from_slice(&serial.buffer);
+
+        serial.box_blur_with(4, false);
+        parallel.box_blur_with(4, true);
+        assert_eq!(serial.buffer, parallel.buffer);
+    }
+}
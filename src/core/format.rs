@@ -13,6 +13,30 @@ pub enum ImageFormat {
     Rgba = 4,
 }
 
+/// 单个 buffer 的最大字节数（WASM32 线性内存上限为 4 GiB）
+pub(crate) const MAX_BUFFER_BYTES: usize = u32::MAX as usize;
+
+impl ImageFormat {
+    /// 每像素字节数（即通道数）
+    #[inline]
+    pub fn bytes_per_pixel(&self) -> usize {
+        *self as usize
+    }
+
+    /// 计算 `width x height` 图像所需的字节数
+    ///
+    /// 使用 usize 检查乘法，溢出或超过 4 GiB 时返回错误，而不是静默回绕
+    pub fn buffer_size(&self, width: u32, height: u32) -> Result<usize, String> {
+        (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|pixels| pixels.checked_mul(self.bytes_per_pixel()))
+            .filter(|&size| size <= MAX_BUFFER_BYTES)
+            .ok_or_else(|| {
+                format!("{width}x{height} {self:?} buffer exceeds {MAX_BUFFER_BYTES} bytes")
+            })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -26,6 +50,23 @@ mod tests {
     }
 
     #[test]
+    fn test_bytes_per_pixel_and_buffer_size() {
+        assert_eq!(ImageFormat::Grayscale.bytes_per_pixel(), 1);
+        assert_eq!(ImageFormat::Rgba.bytes_per_pixel(), 4);
+        assert_eq!(ImageFormat::Rgb.buffer_size(10, 20), Ok(600));
+
+        // u32 乘法会回绕的尺寸也能算出正确结果
+        assert_eq!(
+            ImageFormat::Grayscale.buffer_size(65536, 65535),
+            Ok(65536 * 65535)
+        );
+        // 40000x40000 RGBA 需要 6.4 GB，超出上限
+        assert!(ImageFormat::Rgba.buffer_size(40000, 40000).is_err());
+        assert!(ImageFormat::Rgba.buffer_size(u32::MAX, u32::MAX).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_image_format_clone() {
         let format = ImageFormat::Rgba;
         let cloned = format.clone();
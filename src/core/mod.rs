@@ -1,10 +1,17 @@
 //! 核心模块
 //!
-//! 包含 SharedBuffer 和 ImageFormat
+//! 包含 SharedBuffer、ImageFormat、SamplingMode 和颜色工具
 
 mod buffer;
+pub(crate) mod color;
+pub(crate) mod decode;
+mod effects;
 pub(crate) mod format;
+pub(crate) mod sampling;
 
 // 导出核心类型
 pub use buffer::SharedBuffer;
+pub use color::{LuminanceWeights, pack_rgba, rgb, unpack_rgba};
+pub use decode::DecodeError;
 pub use format::ImageFormat;
+pub use sampling::{EdgeMode, SamplingMode};
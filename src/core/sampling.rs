@@ -0,0 +1,220 @@
+use wasm_bindgen::prelude::*;
+
+/// 采样模式枚举
+/// 定义从源图像取样时使用的插值方式
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SamplingMode {
+    /// 最近邻采样，像素风格（边缘清晰）
+    Nearest = 0,
+    /// 双线性插值，照片风格（过渡平滑）
+    Bilinear = 1,
+}
+
+/// 边缘处理方式
+/// 定义采样坐标或插值邻居超出图像范围时如何取像素
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EdgeMode {
+    /// 钳制到边缘像素
+    #[default]
+    Clamp = 0,
+    /// 以边缘像素为轴镜像反射（`-1 -> 1`，`w -> w - 2`），适合平铺纹理
+    Mirror = 1,
+}
+
+impl EdgeMode {
+    /// 将像素索引映射到 `[0, len)` 范围内
+    #[inline]
+    fn resolve(self, i: i64, len: i64) -> i64 {
+        match self {
+            EdgeMode::Clamp => i.clamp(0, len - 1),
+            EdgeMode::Mirror => {
+                if len <= 1 {
+                    return 0;
+                }
+                let period = 2 * (len - 1);
+                let m = i.rem_euclid(period);
+                if m < len { m } else { period - m }
+            }
+        }
+    }
+}
+
+/// 从像素数据中采样（超出范围的坐标钳制到边缘像素）
+///
+/// 参数含义见 [`sample_with_edge`]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn sample(
+    buffer: &[u8],
+    width: u32,
+    height: u32,
+    channels: usize,
+    x: f32,
+    y: f32,
+    mode: SamplingMode,
+    out: &mut [u8],
+) {
+    sample_with_edge(
+        buffer,
+        width,
+        (0, 0, width, height),
+        channels,
+        x,
+        y,
+        mode,
+        EdgeMode::Clamp,
+        out,
+    );
+}
+
+/// 从像素数据中采样
+///
+/// 只在 `region` 子矩形内取样（如图集中的一帧），坐标相对于子矩形原点。
+/// 坐标为连续坐标：像素 `i` 覆盖区间 `[i, i + 1)`，像素中心位于 `i + 0.5`。
+/// 超出子矩形的像素（包括双线性插值的邻居）按 `edge` 处理，不会读到子矩形之外。
+///
+/// # Arguments
+/// * `buffer` - 源像素数据
+/// * `stride` - 源图像每行像素数（完整图像宽度）
+/// * `region` - 取样子矩形 (x, y, w, h)，须位于图像范围内
+/// * `channels` - 每像素通道数
+/// * `x` - 采样点 x 坐标
+/// * `y` - 采样点 y 坐标
+/// * `mode` - 采样模式
+/// * `edge` - 边缘处理方式
+/// * `out` - 输出，长度至少为 `channels`
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn sample_with_edge(
+    buffer: &[u8],
+    stride: u32,
+    region: (u32, u32, u32, u32),
+    channels: usize,
+    x: f32,
+    y: f32,
+    mode: SamplingMode,
+    edge: EdgeMode,
+    out: &mut [u8],
+) {
+    let (rx, ry, width, height) = region;
+    let pixel = |px: i64, py: i64| {
+        let px = rx as usize + edge.resolve(px, width as i64) as usize;
+        let py = ry as usize + edge.resolve(py, height as i64) as usize;
+        (py * stride as usize + px) * channels
+    };
+
+    match mode {
+        SamplingMode::Nearest => {
+            let base = pixel(x.floor() as i64, y.floor() as i64);
+            out[..channels].copy_from_slice(&buffer[base..base + channels]);
+        }
+        SamplingMode::Bilinear => {
+            // 转换到以像素中心为整数点的坐标系
+            let fx = x - 0.5;
+            let fy = y - 0.5;
+            let x0 = fx.floor();
+            let y0 = fy.floor();
+            let tx = fx - x0;
+            let ty = fy - y0;
+            let (x0, y0) = (x0 as i64, y0 as i64);
+
+            let p00 = pixel(x0, y0);
+            let p10 = pixel(x0 + 1, y0);
+            let p01 = pixel(x0, y0 + 1);
+            let p11 = pixel(x0 + 1, y0 + 1);
+
+            for c in 0..channels {
+                let top = buffer[p00 + c] as f32 * (1.0 - tx) + buffer[p10 + c] as f32 * tx;
+                let bottom = buffer[p01 + c] as f32 * (1.0 - tx) + buffer[p11 + c] as f32 * tx;
+                out[c] = (top * (1.0 - ty) + bottom * ty).round() as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_nearest_clamps_to_edge() {
+        let buffer = [10u8, 20, 30, 40];
+        let mut out = [0u8; 1];
+
+        sample(
+            &buffer,
+            2,
+            2,
+            1,
+            -5.0,
+            -5.0,
+            SamplingMode::Nearest,
+            &mut out,
+        );
+        assert_eq!(out[0], 10);
+
+        sample(&buffer, 2, 2, 1, 9.0, 9.0, SamplingMode::Nearest, &mut out);
+        assert_eq!(out[0], 40);
+    }
+
+    #[test]
+    fn test_sample_bilinear_midpoint() {
+        // 两个像素之间的中点应得到平均值
+        let buffer = [0u8, 200];
+        let mut out = [0u8; 1];
+        sample(&buffer, 2, 1, 1, 1.0, 0.5, SamplingMode::Bilinear, &mut out);
+        assert_eq!(out[0], 100);
+    }
+
+    #[test]
+    fn test_mirror_edge_reflects_neighbor() {
+        let buffer = [0u8, 100, 200];
+        let mut out = [0u8; 1];
+        let at = |edge, out: &mut [u8; 1]| {
+            // x = 2.9：插值邻居为越界的像素 3
+            sample_with_edge(
+                &buffer,
+                3,
+                (0, 0, 3, 1),
+                1,
+                2.9,
+                0.5,
+                SamplingMode::Bilinear,
+                edge,
+                out,
+            );
+            out[0]
+        };
+
+        // 钳制：邻居为像素 2 本身；镜像：邻居为像素 1
+        assert_eq!(at(EdgeMode::Clamp, &mut out), 200);
+        assert_eq!(at(EdgeMode::Mirror, &mut out), 160);
+    }
+
+    #[test]
+    fn test_region_does_not_bleed() {
+        // 2x2 图像，只在右列 (1, 0, 1, 2) 内取样，插值不会读到左列
+        let buffer = [0u8, 100, 0, 200];
+        let mut out = [0u8; 1];
+        sample_with_edge(
+            &buffer,
+            2,
+            (1, 0, 1, 2),
+            1,
+            0.0,
+            1.0,
+            SamplingMode::Bilinear,
+            EdgeMode::Clamp,
+            &mut out,
+        );
+        assert_eq!(out[0], 150);
+    }
+
+    #[test]
+    fn test_mirror_resolve() {
+        assert_eq!(EdgeMode::Mirror.resolve(-1, 4), 1);
+        assert_eq!(EdgeMode::Mirror.resolve(4, 4), 2);
+        assert_eq!(EdgeMode::Mirror.resolve(7, 4), 1);
+        assert_eq!(EdgeMode::Mirror.resolve(5, 1), 0);
+    }
+}
@@ -7,7 +7,12 @@ pub mod math;
 pub mod scene;
 
 // 导出核心类型
+pub use core::DecodeError;
+pub use core::EdgeMode;
 pub use core::ImageFormat;
+pub use core::LuminanceWeights;
+pub use core::SamplingMode;
 pub use core::SharedBuffer;
-pub use math::{Matrix3x3, MatrixOperations, Transform2D, Vec2};
-pub use scene::{ImageSprite, Scene, Sprite, WasmScene};
+pub use core::{pack_rgba, rgb, unpack_rgba};
+pub use math::{Matrix3x3, MatrixOperations, Transform2D, TransformOrder, Vec2};
+pub use scene::{AaMode, BlendMode, ImageSprite, Scene, Sprite, SpritePool, ToneMap, WasmScene};
@@ -65,6 +65,47 @@ impl Matrix3x3 {
         &self.data
     }
 
+    /// 直接构建 `T * R * S` 组合矩阵
+    ///
+    /// 只计算一次 sin/cos，省去两次完整的 3x3 矩阵乘法
+    #[inline]
+    pub fn from_trs(translation: Vec2, rotation: f32, scale: Vec2) -> Self {
+        let (sin, cos) = rotation.sin_cos();
+        Self::from_array([
+            cos * scale.x,
+            -sin * scale.y,
+            translation.x,
+            sin * scale.x,
+            cos * scale.y,
+            translation.y,
+            0.0,
+            0.0,
+            1.0,
+        ])
+    }
+
+    /// 创建切变矩阵 `[1, kx, 0, ky, 1, 0, 0, 0, 1]`
+    ///
+    /// `kx` 使 x 随 y 偏移（斜体效果），`ky` 使 y 随 x 偏移
+    #[inline]
+    pub fn shear(kx: f32, ky: f32) -> Self {
+        Self::from_array([1.0, kx, 0.0, ky, 1.0, 0.0, 0.0, 0.0, 1.0])
+    }
+
+    /// 转置矩阵
+    pub fn transpose(&self) -> Self {
+        let m = &self.data;
+        Self::from_array([m[0], m[3], m[6], m[1], m[4], m[7], m[2], m[5], m[8]])
+    }
+
+    /// 近似相等比较（每个元素的差值都不超过 `epsilon`）
+    pub fn approx_eq(&self, other: &Matrix3x3, epsilon: f32) -> bool {
+        self.data
+            .iter()
+            .zip(other.data.iter())
+            .all(|(a, b)| (a - b).abs() <= epsilon)
+    }
+
     /// 计算矩阵行列式（用于判断是否可逆）
     pub fn determinant(&self) -> f32 {
         let m = &self.data;
@@ -72,6 +113,55 @@ impl Matrix3x3 {
             + m[2] * (m[3] * m[7] - m[4] * m[6])
     }
 
+    /// 提取缩放分量（两个基向量的长度）
+    ///
+    /// 只需要缩放时比完整分解更轻量，旋转不影响结果
+    pub fn extract_scale(&self) -> Vec2 {
+        let m = &self.data;
+        Vec2::new(
+            Vec2::new(m[0], m[3]).length(),
+            Vec2::new(m[1], m[4]).length(),
+        )
+    }
+
+    /// 分解为 (平移, 旋转弧度, 缩放)，即 `T * R * S` 的各分量
+    ///
+    /// 行列式为负（含镜像）时将 y 轴缩放取负；含切变的矩阵无法精确还原
+    pub fn decompose(&self) -> (Vec2, f32, Vec2) {
+        let m = &self.data;
+        let translation = Vec2::new(m[2], m[5]);
+        let rotation = m[3].atan2(m[0]);
+        let mut scale = self.extract_scale();
+        if m[0] * m[4] - m[1] * m[3] < 0.0 {
+            scale.y = -scale.y;
+        }
+        (translation, rotation, scale)
+    }
+
+    /// 判断是否为均匀缩放（两个轴的缩放差值不超过 `epsilon`）
+    pub fn is_uniform_scale(&self, epsilon: f32) -> bool {
+        let scale = self.extract_scale();
+        (scale.x - scale.y).abs() <= epsilon
+    }
+
+    /// 矩阵的 n 次幂（快速幂），`powi(0)` 返回单位矩阵
+    ///
+    /// 可用于将同一变换重复作用 n 次，例如螺旋排布
+    pub fn powi(&self, n: u32) -> Self {
+        let mut result = Self::identity();
+        let mut base = *self;
+        let mut n = n;
+
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.multiply(&base);
+            }
+            base = base.multiply(&base);
+            n >>= 1;
+        }
+        result
+    }
+
     /// 计算逆矩阵
     pub fn inverse(&self) -> Option<Self> {
         let det = self.determinant();
@@ -217,6 +307,46 @@ mod tests {
         assert!((result.y - 12.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_extract_scale_non_uniform() {
+        let m = Matrix3x3::rotation(PI / 3.0).multiply(&Matrix3x3::scaling(2.0, 3.0));
+        let scale = m.extract_scale();
+
+        assert!((scale.x - 2.0).abs() < 1e-5);
+        assert!((scale.y - 3.0).abs() < 1e-5);
+        assert!(!m.is_uniform_scale(1e-5));
+    }
+
+    #[test]
+    fn test_is_uniform_scale() {
+        let m = Matrix3x3::translation(5.0, 5.0)
+            .multiply(&Matrix3x3::rotation(PI / 4.0))
+            .multiply(&Matrix3x3::scaling(1.5, 1.5));
+        assert!(m.is_uniform_scale(1e-5));
+    }
+
+    #[test]
+    fn test_powi() {
+        let rotate = Matrix3x3::rotation(PI / 2.0);
+        let result = rotate.powi(4);
+        let identity = Matrix3x3::identity();
+        assert!(result.approx_eq(&identity, 1e-5));
+
+        assert_eq!(rotate.powi(0), identity);
+
+        let translate = Matrix3x3::translation(1.0, 2.0).powi(5);
+        assert!((translate.get(0, 2) - 5.0).abs() < 1e-6);
+        assert!((translate.get(1, 2) - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_shear() {
+        let m = Matrix3x3::shear(0.5, 0.0);
+        let p = m.transform_point(Vec2::new(2.0, 4.0));
+        assert!((p.x - 4.0).abs() < 1e-6 && (p.y - 4.0).abs() < 1e-6);
+        assert!((m.determinant() - 1.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_inverse() {
         let m = Matrix3x3::translation(10.0, 20.0);
@@ -225,8 +355,67 @@ mod tests {
 
         // 应该得到单位矩阵
         let identity = Matrix3x3::identity();
-        for i in 0..9 {
-            assert!((result.as_array()[i] - identity.as_array()[i]).abs() < 1e-6);
-        }
+        assert!(result.approx_eq(&identity, 1e-6));
+    }
+
+    #[test]
+    fn test_decompose_round_trip() {
+        let m = Matrix3x3::translation(12.0, -7.5)
+            .multiply(&Matrix3x3::rotation(0.75))
+            .multiply(&Matrix3x3::scaling(2.0, 0.5));
+        let (translation, rotation, scale) = m.decompose();
+
+        assert!((translation.x - 12.0).abs() < 1e-5);
+        assert!((translation.y + 7.5).abs() < 1e-5);
+        assert!((rotation - 0.75).abs() < 1e-5);
+        assert!((scale.x - 2.0).abs() < 1e-5);
+        assert!((scale.y - 0.5).abs() < 1e-5);
+
+        // 镜像：负缩放体现在 y 轴上，重新组合后与原矩阵一致
+        let flipped = Matrix3x3::rotation(PI / 6.0).multiply(&Matrix3x3::scaling(-3.0, 2.0));
+        let (t, r, s) = flipped.decompose();
+        assert!(s.y < 0.0);
+        let rebuilt = Matrix3x3::translation(t.x, t.y)
+            .multiply(&Matrix3x3::rotation(r))
+            .multiply(&Matrix3x3::scaling(s.x, s.y));
+        assert!(rebuilt.approx_eq(&flipped, 1e-5));
+    }
+
+    #[test]
+    fn test_from_trs_matches_product() {
+        let (t, r, s) = (Vec2::new(12.0, -3.5), 2.1, Vec2::new(-1.5, 0.25));
+        let stepwise = Matrix3x3::translation(t.x, t.y)
+            .multiply(&Matrix3x3::rotation(r))
+            .multiply(&Matrix3x3::scaling(s.x, s.y));
+        assert!(Matrix3x3::from_trs(t, r, s).approx_eq(&stepwise, 1e-6));
+    }
+
+    #[test]
+    fn test_transpose() {
+        let m = Matrix3x3::from_array([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+        let t = m.transpose();
+        assert_eq!(t.get(0, 1), 4.0);
+        assert_eq!(t.get(2, 0), 3.0);
+        assert_eq!(t.transpose(), m);
+
+        // 旋转矩阵的转置即其逆矩阵
+        let rotate = Matrix3x3::rotation(0.4);
+        assert!(
+            rotate
+                .multiply(&rotate.transpose())
+                .approx_eq(&Matrix3x3::identity(), 1e-6)
+        );
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = Matrix3x3::translation(1.0, 2.0);
+        let mut b = a;
+        b.set(0, 2, 1.0 + 1e-7);
+        assert!(a.approx_eq(&b, 1e-6));
+        assert_ne!(a, b);
+
+        b.set(1, 1, 1.01);
+        assert!(!a.approx_eq(&b, 1e-6));
     }
 }
@@ -8,5 +8,5 @@ mod transform;
 mod vec2;
 
 pub use matrix::{Matrix3x3, MatrixOperations};
-pub use transform::Transform2D;
+pub use transform::{Transform2D, TransformOrder};
 pub use vec2::Vec2;
@@ -4,6 +4,17 @@
 
 use super::{Matrix3x3, MatrixOperations, Vec2};
 
+/// 变换组合顺序
+///
+/// 矩阵从右向左作用于点，`Trs` 表示先缩放、再旋转、最后平移
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransformOrder {
+    /// T * R * S：先缩放再旋转（默认，标准顺序）
+    Trs,
+    /// T * S * R：先旋转再缩放（部分导入数据使用）
+    Tsr,
+}
+
 /// 2D 变换组件
 ///
 /// 封装位置、旋转、缩放，提供便捷的变换操作接口
@@ -15,10 +26,20 @@ pub struct Transform2D {
     pub rotation: f32,
     /// 缩放
     pub scale: Vec2,
+    /// 切变系数（x 随 y 偏移、y 随 x 偏移）
+    pub skew: Vec2,
     /// 锚点（0-1 范围，相对于精灵尺寸）
     pub anchor: Vec2,
+    /// 变换组合顺序
+    order: TransformOrder,
+    /// 水平翻转
+    flip_x: bool,
+    /// 垂直翻转
+    flip_y: bool,
     /// 缓存的变换矩阵
     matrix_cache: Option<Matrix3x3>,
+    /// 缓存的逆矩阵
+    inverse_cache: Option<Matrix3x3>,
 }
 
 impl Transform2D {
@@ -28,16 +49,26 @@ impl Transform2D {
             position: Vec2::zero(),
             rotation: 0.0,
             scale: Vec2::one(),
+            skew: Vec2::zero(),
             anchor: Vec2::new(0.5, 0.5), // 默认中心锚点
+            order: TransformOrder::Trs,
+            flip_x: false,
+            flip_y: false,
             matrix_cache: None,
+            inverse_cache: None,
         }
     }
 
     /// 设置位置
+    ///
+    /// 非有限值（NaN、无穷）会被忽略，保留原位置
     #[inline]
     pub fn set_position(&mut self, x: f32, y: f32) -> &mut Self {
-        self.position = Vec2::new(x, y);
-        self.invalidate_cache();
+        let position = Vec2::new(x, y);
+        if position.is_finite() {
+            self.position = position;
+            self.invalidate_cache();
+        }
         self
     }
 
@@ -57,18 +88,59 @@ impl Transform2D {
         self
     }
 
+    /// 设置旋转（角度），按 `step` 吸附到最近的倍数
+    ///
+    /// 适合只允许 90° 旋转的瓦片编辑器等场景，`step <= 0` 时不吸附
+    #[inline]
+    pub fn set_rotation_snapped(&mut self, degrees: f32, step: f32) -> &mut Self {
+        let snapped = if step > 0.0 {
+            (degrees / step).round() * step
+        } else {
+            degrees
+        };
+        self.set_rotation_degrees(snapped)
+    }
+
     /// 设置缩放
+    ///
+    /// 非有限值（NaN、无穷）会被忽略，保留原缩放
     #[inline]
     pub fn set_scale(&mut self, sx: f32, sy: f32) -> &mut Self {
-        self.scale = Vec2::new(sx, sy);
-        self.invalidate_cache();
+        let scale = Vec2::new(sx, sy);
+        if scale.is_finite() {
+            self.scale = scale;
+            self.invalidate_cache();
+        }
         self
     }
 
     /// 设置均匀缩放
     #[inline]
     pub fn set_uniform_scale(&mut self, s: f32) -> &mut Self {
-        self.scale = Vec2::new(s, s);
+        self.set_scale(s, s)
+    }
+
+    /// 设置切变系数
+    ///
+    /// `kx` 使精灵沿 x 方向倾斜（斜体效果），`ky` 沿 y 方向倾斜
+    #[inline]
+    pub fn set_skew(&mut self, kx: f32, ky: f32) -> &mut Self {
+        self.skew = Vec2::new(kx, ky);
+        self.invalidate_cache();
+        self
+    }
+
+    /// 一次性设置位置、旋转（弧度）和缩放，只使缓存失效一次
+    ///
+    /// 与分别调用各个 setter 的结果相同，非有限的位置或缩放同样会被忽略
+    pub fn set_trs(&mut self, position: Vec2, rotation: f32, scale: Vec2) -> &mut Self {
+        if position.is_finite() {
+            self.position = position;
+        }
+        self.rotation = rotation;
+        if scale.is_finite() {
+            self.scale = scale;
+        }
         self.invalidate_cache();
         self
     }
@@ -81,6 +153,46 @@ impl Transform2D {
         self
     }
 
+    /// 设置变换组合顺序
+    #[inline]
+    pub fn set_order(&mut self, order: TransformOrder) -> &mut Self {
+        self.order = order;
+        self.invalidate_cache();
+        self
+    }
+
+    /// 获取变换组合顺序
+    #[inline]
+    pub fn order(&self) -> TransformOrder {
+        self.order
+    }
+
+    /// 设置水平翻转
+    #[inline]
+    pub fn set_flip_x(&mut self, flip: bool) -> &mut Self {
+        self.flip_x = flip;
+        self
+    }
+
+    /// 设置垂直翻转
+    #[inline]
+    pub fn set_flip_y(&mut self, flip: bool) -> &mut Self {
+        self.flip_y = flip;
+        self
+    }
+
+    /// 是否水平翻转
+    #[inline]
+    pub fn flip_x(&self) -> bool {
+        self.flip_x
+    }
+
+    /// 是否垂直翻转
+    #[inline]
+    pub fn flip_y(&self) -> bool {
+        self.flip_y
+    }
+
     /// 平移
     #[inline]
     pub fn translate(&mut self, dx: f32, dy: f32) -> &mut Self {
@@ -90,6 +202,20 @@ impl Transform2D {
         self
     }
 
+    /// 按速度平移并返回衰减后的速度（惯性拖拽）
+    ///
+    /// 平移 `velocity * dt`，返回 `velocity * damping^dt`，
+    /// 调用方在下一帧将返回值作为新的速度传入
+    ///
+    /// # Arguments
+    /// * `velocity` - 当前速度（单位/秒）
+    /// * `dt` - 帧间隔（秒）
+    /// * `damping` - 每秒保留的速度比例（0-1）
+    pub fn apply_velocity(&mut self, velocity: Vec2, dt: f32, damping: f32) -> Vec2 {
+        self.translate(velocity.x * dt, velocity.y * dt);
+        velocity * damping.powf(dt)
+    }
+
     /// 旋转（弧度）
     #[inline]
     pub fn rotate(&mut self, angle: f32) -> &mut Self {
@@ -117,31 +243,65 @@ impl Transform2D {
 
     /// 获取变换矩阵
     ///
-    /// 变换顺序：缩放 -> 旋转 -> 平移
+    /// 默认变换顺序：缩放 -> 切变 -> 旋转 -> 平移，可通过 `set_order` 调整
     /// 注意：锚点需要在渲染时与精灵尺寸结合使用
     pub fn matrix(&mut self) -> Matrix3x3 {
         if let Some(cached) = self.matrix_cache {
             return cached;
         }
 
-        // 构建变换矩阵：T * R * S
+        // 无切变的 TRS 是最常见的情况，直接构建组合矩阵
+        if self.order == TransformOrder::Trs && self.skew.is_zero(0.0) {
+            let matrix = Matrix3x3::from_trs(self.position, self.rotation, self.scale);
+            self.matrix_cache = Some(matrix);
+            return matrix;
+        }
+
+        // 构建变换矩阵：T * R * Shear * S 或 T * S * Shear * R
         let translate = Matrix3x3::translation(self.position.x, self.position.y);
         let rotate = Matrix3x3::rotation(self.rotation);
+        let shear = Matrix3x3::shear(self.skew.x, self.skew.y);
         let scale = Matrix3x3::scaling(self.scale.x, self.scale.y);
 
-        let matrix = translate.multiply(&rotate).multiply(&scale);
+        let matrix = match self.order {
+            TransformOrder::Trs => translate
+                .multiply(&rotate)
+                .multiply(&shear)
+                .multiply(&scale),
+            TransformOrder::Tsr => translate
+                .multiply(&scale)
+                .multiply(&shear)
+                .multiply(&rotate),
+        };
         self.matrix_cache = Some(matrix);
         matrix
     }
 
-    /// 获取带尺寸的完整变换矩阵（考虑锚点）
+    /// 获取带尺寸的完整变换矩阵（考虑锚点和翻转）
     pub fn matrix_with_size(&mut self, width: f32, height: f32) -> Matrix3x3 {
         // 锚点偏移
         let anchor_offset = Matrix3x3::translation(-self.anchor.x * width, -self.anchor.y * height);
 
         // 完整变换：T * R * S * AnchorOffset
         let base_matrix = self.matrix();
-        base_matrix.multiply(&anchor_offset)
+        let matrix = base_matrix.multiply(&anchor_offset);
+        if !self.flip_x && !self.flip_y {
+            return matrix;
+        }
+
+        // 翻转在精灵本地坐标内进行（x -> width - x），占据的区域不变，锚点不漂移
+        let (sx, tx) = if self.flip_x {
+            (-1.0, width)
+        } else {
+            (1.0, 0.0)
+        };
+        let (sy, ty) = if self.flip_y {
+            (-1.0, height)
+        } else {
+            (1.0, 0.0)
+        };
+        let flip = Matrix3x3::translation(tx, ty).multiply(&Matrix3x3::scaling(sx, sy));
+        matrix.multiply(&flip)
     }
 
     /// 变换一个点
@@ -150,10 +310,45 @@ impl Transform2D {
         self.matrix().transform_point(point)
     }
 
+    /// 将世界坐标点变换回本地坐标（`transform_point` 的逆运算）
+    ///
+    /// 逆矩阵会被缓存；矩阵不可逆（如缩放为 0）时返回 `None`
+    pub fn inverse_transform_point(&mut self, point: Vec2) -> Option<Vec2> {
+        let inverse = match self.inverse_cache {
+            Some(cached) => cached,
+            None => {
+                let inverse = self.matrix().inverse()?;
+                self.inverse_cache = Some(inverse);
+                inverse
+            }
+        };
+        Some(inverse.transform_point(point))
+    }
+
+    /// 近似相等比较（位置、旋转、缩放、切变、锚点的差值都不超过 `epsilon`，翻转和变换顺序相同）
+    ///
+    /// 浮点数不适合按位比较，可用于以变换作为缓存键的场景
+    pub fn approx_eq(&self, other: &Transform2D, epsilon: f32) -> bool {
+        let close = |a: f32, b: f32| (a - b).abs() <= epsilon;
+        close(self.position.x, other.position.x)
+            && close(self.position.y, other.position.y)
+            && close(self.rotation, other.rotation)
+            && close(self.scale.x, other.scale.x)
+            && close(self.scale.y, other.scale.y)
+            && close(self.skew.x, other.skew.x)
+            && close(self.skew.y, other.skew.y)
+            && close(self.anchor.x, other.anchor.x)
+            && close(self.anchor.y, other.anchor.y)
+            && self.flip_x == other.flip_x
+            && self.flip_y == other.flip_y
+            && self.order == other.order
+    }
+
     /// 清除缓存
     #[inline]
     fn invalidate_cache(&mut self) {
         self.matrix_cache = None;
+        self.inverse_cache = None;
     }
 }
 
@@ -168,6 +363,91 @@ mod tests {
     use super::*;
     use std::f32::consts::PI;
 
+    #[test]
+    fn test_flip_keeps_footprint() {
+        let mut transform = Transform2D::new();
+        transform.set_position(10.0, 10.0).set_flip_x(true);
+
+        // 中心锚点 4x2：本地左上角翻转后映射到右上角
+        let matrix = transform.matrix_with_size(4.0, 2.0);
+        let p = matrix.transform_point(Vec2::new(0.0, 0.0));
+        assert!((p.x - 12.0).abs() < 1e-5 && (p.y - 9.0).abs() < 1e-5);
+        let p = matrix.transform_point(Vec2::new(4.0, 2.0));
+        assert!((p.x - 8.0).abs() < 1e-5 && (p.y - 11.0).abs() < 1e-5);
+        assert!(matrix.inverse().is_some());
+    }
+
+    #[test]
+    fn test_skew_x_moves_bottom_edge_only() {
+        let mut transform = Transform2D::new();
+        transform.set_anchor(0.0, 0.0);
+        let before = transform.matrix_with_size(4.0, 4.0);
+        transform.set_skew(0.5, 0.0);
+
+        // set_skew 使缓存失效，矩阵随之更新
+        let matrix = transform.matrix_with_size(4.0, 4.0);
+        assert_ne!(matrix, before);
+
+        let origin = matrix.transform_point(Vec2::new(0.0, 0.0));
+        assert!(origin.is_zero(1e-6));
+        let corner = matrix.transform_point(Vec2::new(0.0, 4.0));
+        assert!((corner.x - 2.0).abs() < 1e-6 && (corner.y - 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_inverse_transform_point_round_trip() {
+        let mut transform = Transform2D::new();
+        transform
+            .set_position(10.0, -5.0)
+            .set_rotation(0.6)
+            .set_scale(2.0, 3.0);
+
+        let local = Vec2::new(1.5, -2.0);
+        let world = transform.transform_point(local);
+        let back = transform.inverse_transform_point(world).unwrap();
+        assert!((back - local).is_zero(1e-5));
+
+        // 修改变换后缓存失效
+        transform.translate(1.0, 0.0);
+        let back = transform.inverse_transform_point(world).unwrap();
+        assert!((back - local).length() > 0.1);
+
+        transform.set_scale(0.0, 1.0);
+        assert!(transform.inverse_transform_point(world).is_none());
+    }
+
+    #[test]
+    fn test_set_trs_matches_individual_setters() {
+        let mut a = Transform2D::new();
+        a.set_position(3.0, 4.0)
+            .set_rotation(0.3)
+            .set_scale(2.0, 0.5);
+        let mut b = Transform2D::new();
+        b.set_trs(Vec2::new(3.0, 4.0), 0.3, Vec2::new(2.0, 0.5));
+        assert_eq!(a.matrix(), b.matrix());
+
+        // 修改后缓存随之更新，非有限值被忽略
+        b.set_trs(Vec2::new(f32::NAN, 0.0), 0.0, Vec2::one());
+        assert_eq!(b.position, Vec2::new(3.0, 4.0));
+        assert_ne!(a.matrix(), b.matrix());
+    }
+
+    #[test]
+    fn test_non_finite_input_rejected() {
+        let mut transform = Transform2D::new();
+        transform.set_position(3.0, 4.0).set_scale(2.0, 2.0);
+
+        transform.set_position(f32::NAN, 1.0);
+        transform.set_scale(f32::INFINITY, 1.0);
+        transform.set_uniform_scale(f32::NAN);
+
+        // 非法输入被忽略，矩阵保持有限
+        assert_eq!(transform.position, Vec2::new(3.0, 4.0));
+        assert_eq!(transform.scale, Vec2::new(2.0, 2.0));
+        let point = transform.transform_point(Vec2::new(1.0, 1.0));
+        assert!(point.is_finite());
+    }
+
     #[test]
     fn test_default_transform() {
         let mut transform = Transform2D::new();
@@ -192,6 +472,79 @@ mod tests {
         assert!((transform.scale.x - 2.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_set_rotation_snapped() {
+        let mut transform = Transform2D::new();
+
+        // 47° 距离 90° 更近
+        transform.set_rotation_snapped(47.0, 90.0);
+        assert!((transform.rotation - PI / 2.0).abs() < 1e-6);
+
+        transform.set_rotation_snapped(44.0, 90.0);
+        assert!(transform.rotation.abs() < 1e-6);
+
+        // step <= 0 时不吸附
+        transform.set_rotation_snapped(47.0, 0.0);
+        assert!((transform.rotation - 47f32.to_radians()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_apply_velocity_decays() {
+        let mut transform = Transform2D::new();
+        let mut velocity = Vec2::new(100.0, 0.0);
+        let mut last_step = f32::MAX;
+
+        for _ in 0..10 {
+            let before = transform.position.x;
+            let next = transform.apply_velocity(velocity, 0.1, 0.5);
+            let step = transform.position.x - before;
+
+            // 每帧移动距离递减，速度逐渐趋近于零
+            assert!(step > 0.0 && step < last_step);
+            assert!(next.length() < velocity.length());
+            last_step = step;
+            velocity = next;
+        }
+        assert!(velocity.x < 100.0 * 0.5 + 1e-3);
+    }
+
+    #[test]
+    fn test_transform_order_differs() {
+        let mut trs = Transform2D::new();
+        trs.set_rotation(PI / 2.0).set_scale(2.0, 1.0);
+        let mut tsr = trs;
+        tsr.set_order(TransformOrder::Tsr);
+
+        let point = Vec2::new(1.0, 0.0);
+        // TRS：先缩放 (2, 0)，再旋转 -> (0, 2)
+        let a = trs.transform_point(point);
+        assert!(a.x.abs() < 1e-6 && (a.y - 2.0).abs() < 1e-6);
+
+        // TSR：先旋转 (0, 1)，再缩放 -> (0, 1)
+        let b = tsr.transform_point(point);
+        assert!(b.x.abs() < 1e-6 && (b.y - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let mut a = Transform2D::new();
+        a.set_position(10.0, 20.0)
+            .set_rotation(0.5)
+            .set_scale(2.0, 3.0);
+
+        let mut b = a;
+        b.translate(1e-5, -1e-5).rotate(1e-5);
+        assert!(a.approx_eq(&b, 1e-4));
+
+        b.set_anchor(0.0, 0.0);
+        assert!(!a.approx_eq(&b, 1e-4));
+
+        // 变换顺序不同，矩阵不同
+        let mut c = a;
+        c.set_order(TransformOrder::Tsr);
+        assert!(!a.approx_eq(&c, 1e-4));
+    }
+
     #[test]
     fn test_translation() {
         let mut transform = Transform2D::new();
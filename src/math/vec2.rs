@@ -30,6 +30,25 @@ impl Vec2 {
         Self { x: 1.0, y: 1.0 }
     }
 
+    /// 由角度（弧度）创建单位向量
+    #[inline]
+    pub fn from_angle(radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self { x: cos, y: sin }
+    }
+
+    /// 是否在 `epsilon` 范围内接近零向量
+    #[inline]
+    pub fn is_zero(&self, epsilon: f32) -> bool {
+        self.x.abs() <= epsilon && self.y.abs() <= epsilon
+    }
+
+    /// 两个分量是否都是有限值（非 NaN、非无穷）
+    #[inline]
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+
     /// 向量长度的平方（避免开方运算）
     #[inline]
     pub fn length_squared(&self) -> f32 {
@@ -42,6 +61,33 @@ impl Vec2 {
         self.length_squared().sqrt()
     }
 
+    /// 到另一点距离的平方（避免开方运算）
+    #[inline]
+    pub fn distance_squared(&self, other: &Vec2) -> f32 {
+        (*other - *self).length_squared()
+    }
+
+    /// 到另一点的距离
+    #[inline]
+    pub fn distance(&self, other: &Vec2) -> f32 {
+        self.distance_squared(other).sqrt()
+    }
+
+    /// 线性插值，`t` 钳制在 0.0-1.0 之间
+    #[inline]
+    pub fn lerp(&self, other: &Vec2, t: f32) -> Self {
+        self.lerp_unclamped(other, t.clamp(0.0, 1.0))
+    }
+
+    /// 线性插值，`t` 不做钳制（超出 0.0-1.0 时外插）
+    #[inline]
+    pub fn lerp_unclamped(&self, other: &Vec2, t: f32) -> Self {
+        Self {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+        }
+    }
+
     /// 归一化向量
     #[inline]
     pub fn normalize(&self) -> Self {
@@ -67,6 +113,55 @@ impl Vec2 {
     pub fn cross(&self, other: &Vec2) -> f32 {
         self.x * other.y - self.y * other.x
     }
+
+    /// 逐分量取最小值
+    #[inline]
+    pub fn min(&self, other: &Vec2) -> Self {
+        Self {
+            x: self.x.min(other.x),
+            y: self.y.min(other.y),
+        }
+    }
+
+    /// 逐分量取最大值
+    #[inline]
+    pub fn max(&self, other: &Vec2) -> Self {
+        Self {
+            x: self.x.max(other.x),
+            y: self.y.max(other.y),
+        }
+    }
+
+    /// 逐分量钳制到 `[lo, hi]`（要求 `lo` 的分量不大于 `hi`）
+    #[inline]
+    pub fn clamp(&self, lo: Vec2, hi: Vec2) -> Self {
+        Self {
+            x: self.x.clamp(lo.x, hi.x),
+            y: self.y.clamp(lo.y, hi.y),
+        }
+    }
+
+    /// 绕原点旋转（弧度，正方向为 x 轴转向 y 轴）
+    #[inline]
+    pub fn rotate(&self, radians: f32) -> Self {
+        let (sin, cos) = radians.sin_cos();
+        Self {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+
+    /// 与 x 轴正方向的夹角（弧度，范围 -π 到 π）
+    #[inline]
+    pub fn angle(&self) -> f32 {
+        self.y.atan2(self.x)
+    }
+
+    /// 与另一向量的夹角（弧度，范围 0 到 π，不区分方向）
+    #[inline]
+    pub fn angle_between(&self, other: &Vec2) -> f32 {
+        self.cross(other).atan2(self.dot(other)).abs()
+    }
 }
 
 impl Add for Vec2 {
@@ -131,4 +226,64 @@ mod tests {
         let b = Vec2::new(0.0, 1.0);
         assert!((a.dot(&b)).abs() < 1e-6); // 垂直向量点积为0
     }
+
+    #[test]
+    fn test_vec2_is_zero_and_finite() {
+        assert!(Vec2::zero().is_zero(0.0));
+        assert!(Vec2::new(1e-7, -1e-7).is_zero(1e-6));
+        assert!(!Vec2::new(0.1, 0.0).is_zero(1e-6));
+
+        assert!(Vec2::new(1.0, 2.0).is_finite());
+        assert!(!Vec2::new(f32::NAN, 0.0).is_finite());
+        assert!(!Vec2::new(0.0, f32::INFINITY).is_finite());
+    }
+
+    #[test]
+    fn test_vec2_rotation_and_angles() {
+        use std::f32::consts::{FRAC_PI_2, PI};
+
+        let v = Vec2::new(1.0, 0.0).rotate(FRAC_PI_2);
+        assert!((v - Vec2::new(0.0, 1.0)).is_zero(1e-6));
+
+        assert!((Vec2::new(0.0, 1.0).angle() - FRAC_PI_2).abs() < 1e-6);
+        assert!((Vec2::new(-1.0, 0.0).angle() - PI).abs() < 1e-6);
+
+        let a = Vec2::new(1.0, 0.0);
+        let b = Vec2::new(0.0, 1.0);
+        assert!((a.angle_between(&b) - FRAC_PI_2).abs() < 1e-6);
+        assert!((b.angle_between(&a) - FRAC_PI_2).abs() < 1e-6);
+
+        let d = Vec2::from_angle(PI);
+        assert!((d - Vec2::new(-1.0, 0.0)).is_zero(1e-6));
+    }
+
+    #[test]
+    fn test_vec2_lerp_and_distance() {
+        let a = Vec2::new(0.0, 0.0);
+        let b = Vec2::new(3.0, 4.0);
+
+        assert_eq!(a.lerp(&b, 0.5), Vec2::new(1.5, 2.0));
+        assert_eq!(a.lerp(&b, 2.0), b);
+        assert_eq!(a.lerp(&b, -1.0), a);
+        assert_eq!(a.lerp_unclamped(&b, 2.0), Vec2::new(6.0, 8.0));
+
+        assert_eq!(a.distance_squared(&b), 25.0);
+        assert!((a.distance(&b) - 5.0).abs() < 1e-6);
+        assert!((Vec2::new(1.0, 1.0).distance(&Vec2::new(6.0, 13.0)) - 13.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_vec2_min_max_clamp() {
+        let a = Vec2::new(-3.0, 5.0);
+        let b = Vec2::new(2.0, -1.0);
+
+        assert_eq!(a.min(&b), Vec2::new(-3.0, -1.0));
+        assert_eq!(a.max(&b), Vec2::new(2.0, 5.0));
+
+        let lo = Vec2::new(-1.0, -2.0);
+        let hi = Vec2::new(1.0, 2.0);
+        assert_eq!(a.clamp(lo, hi), Vec2::new(-1.0, 2.0));
+        assert_eq!(b.clamp(lo, hi), Vec2::new(1.0, -1.0));
+        assert_eq!(Vec2::new(0.5, -0.5).clamp(lo, hi), Vec2::new(0.5, -0.5));
+    }
 }
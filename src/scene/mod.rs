@@ -2,10 +2,13 @@
 //!
 //! 提供类似 Three.js 的场景管理结构，支持精灵图的渲染和变换。
 
+mod pool;
+#[allow(clippy::module_inception)]
 mod scene;
 pub mod sprite;
 mod wasm;
 
-pub use scene::Scene;
-pub use sprite::{ImageSprite, Sprite};
+pub use pool::SpritePool;
+pub use scene::{Scene, ToneMap};
+pub use sprite::{AaMode, BlendMode, ImageSprite, Sprite};
 pub use wasm::WasmScene;
@@ -0,0 +1,167 @@
+//! 精灵对象池
+//!
+//! 预分配固定数量、固定尺寸的精灵，供粒子系统等高频创建/销毁的场景复用
+
+use std::collections::HashMap;
+
+use super::sprite::{ImageSprite, Sprite};
+use crate::core::format::ImageFormat;
+use crate::math::Transform2D;
+
+/// 精灵对象池
+///
+/// 创建时一次性分配所有精灵，`acquire` 取出空闲精灵，`release` 归还，
+/// 归还后的精灵（包括像素 buffer）会被再次复用，不会重新分配内存。
+#[derive(Debug)]
+pub struct SpritePool {
+    /// 所有预分配的精灵
+    sprites: Vec<ImageSprite>,
+    /// 每个槽位是否正在使用
+    active: Vec<bool>,
+    /// 空闲槽位（栈）
+    free: Vec<usize>,
+    /// 精灵 ID -> 槽位，精灵 ID 在池的生命周期内不变
+    slots: HashMap<u64, usize>,
+}
+
+impl SpritePool {
+    /// 创建对象池，预分配 `capacity` 个 `width x height` 的精灵
+    pub fn new(capacity: usize, width: u32, height: u32, format: ImageFormat) -> Self {
+        let sprites: Vec<ImageSprite> = (0..capacity)
+            .map(|_| ImageSprite::new(width, height, format))
+            .collect();
+        let slots = sprites
+            .iter()
+            .enumerate()
+            .map(|(slot, sprite)| (sprite.id(), slot))
+            .collect();
+        Self {
+            sprites,
+            slots,
+            active: vec![false; capacity],
+            // 逆序入栈，使 acquire 按槽位顺序取出
+            free: (0..capacity).rev().collect(),
+        }
+    }
+
+    /// 池容量
+    pub fn capacity(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// 正在使用的精灵数量
+    pub fn active_count(&self) -> usize {
+        self.sprites.len() - self.free.len()
+    }
+
+    /// 取出一个空闲精灵，池已耗尽时返回 `None`
+    ///
+    /// 取出的精灵变换和渲染状态已重置，像素数据保留上次的内容
+    pub fn acquire(&mut self) -> Option<&mut ImageSprite> {
+        let slot = self.free.pop()?;
+        self.active[slot] = true;
+
+        let sprite = &mut self.sprites[slot];
+        *sprite.transform_mut() = Transform2D::new();
+        sprite.set_z_order(0);
+        sprite.reset_render_state();
+        Some(sprite)
+    }
+
+    /// 归还精灵，ID 不属于本池或已归还时返回 false
+    pub fn release(&mut self, id: u64) -> bool {
+        match self.slot_of(id) {
+            Some(slot) if self.active[slot] => {
+                self.active[slot] = false;
+                self.free.push(slot);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 获取正在使用的精灵
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut ImageSprite> {
+        let slot = self.slot_of(id)?;
+        if self.active[slot] {
+            Some(&mut self.sprites[slot])
+        } else {
+            None
+        }
+    }
+
+    /// 遍历所有正在使用的精灵
+    pub fn active_sprites_mut(&mut self) -> impl Iterator<Item = &mut ImageSprite> {
+        self.sprites
+            .iter_mut()
+            .zip(self.active.iter())
+            .filter(|(_, active)| **active)
+            .map(|(sprite, _)| sprite)
+    }
+
+    fn slot_of(&self, id: u64) -> Option<usize> {
+        self.slots.get(&id).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_exhausts_and_reuses_buffers() {
+        let mut pool = SpritePool::new(3, 4, 4, ImageFormat::Rgba);
+
+        let ids: Vec<(u64, *const u8)> = (0..3)
+            .map(|_| {
+                let sprite = pool.acquire().unwrap();
+                (sprite.id(), sprite.buffer().as_ptr())
+            })
+            .collect();
+        assert!(pool.acquire().is_none());
+        assert_eq!(pool.active_count(), 3);
+
+        assert!(pool.release(ids[0].0));
+        assert!(pool.release(ids[2].0));
+        assert!(!pool.release(ids[2].0)); // 重复归还
+        assert_eq!(pool.active_count(), 1);
+
+        // 再次取出的是原来的精灵和 buffer
+        for _ in 0..2 {
+            let sprite = pool.acquire().unwrap();
+            let (_, ptr) = ids.iter().find(|(id, _)| *id == sprite.id()).unwrap();
+            assert_eq!(sprite.buffer().as_ptr(), *ptr);
+            assert_eq!(sprite.buffer().len(), 4 * 4 * 4);
+        }
+        assert!(pool.acquire().is_none());
+        assert_eq!(pool.capacity(), 3);
+    }
+
+    #[test]
+    fn test_acquire_resets_transform() {
+        let mut pool = SpritePool::new(1, 2, 2, ImageFormat::Rgba);
+        let id = {
+            let sprite = pool.acquire().unwrap();
+            sprite.set_position(10.0, 20.0);
+            sprite.id()
+        };
+        pool.release(id);
+
+        let sprite = pool.acquire().unwrap();
+        assert_eq!(sprite.transform().position.x, 0.0);
+        assert_eq!(sprite.transform().position.y, 0.0);
+    }
+
+    #[test]
+    fn test_release_many_by_id() {
+        let mut pool = SpritePool::new(1000, 1, 1, ImageFormat::Rgba);
+        let ids: Vec<u64> = (0..1000).map(|_| pool.acquire().unwrap().id()).collect();
+        for &id in ids.iter().rev() {
+            assert!(pool.get_mut(id).is_some());
+            assert!(pool.release(id));
+        }
+        assert_eq!(pool.active_count(), 0);
+        assert!(!pool.release(ids[0]));
+        assert!(!pool.release(u64::MAX));
+    }
+}
@@ -2,7 +2,65 @@
 //!
 //! 类似 Three.js 的场景结构，管理所有精灵并渲染到 buffer
 
-use super::sprite::Sprite;
+use std::collections::{HashMap, HashSet};
+
+use super::pool::SpritePool;
+use super::sprite::{
+    BlendMode, Sprite, blend_pixel, clip_region, transformed_bounds, transformed_corners,
+};
+use crate::core::color::{pack_rgba, unpack_rgba};
+use crate::core::format::ImageFormat;
+use crate::core::sampling::{SamplingMode, sample};
+use crate::math::{Matrix3x3, MatrixOperations, Transform2D, Vec2};
+
+/// 背景图像 - 拉伸填满整个场景
+#[derive(Debug, Clone)]
+struct BackgroundImage {
+    /// 图像数据
+    buffer: Vec<u8>,
+    /// 宽度
+    width: u32,
+    /// 高度
+    height: u32,
+    /// 图像格式
+    format: ImageFormat,
+    /// 拉伸时的采样模式
+    sampling: SamplingMode,
+}
+
+/// 色调映射 - 累积缓冲转换回 8 位 buffer 时使用
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMap {
+    /// 直接截断到 255
+    Clamp,
+    /// Reinhard 映射 `c / (1 + c)`，平滑压缩高光
+    Reinhard,
+}
+
+impl ToneMap {
+    /// 将累积值（0-255 范围，可超出）映射为 8 位通道值
+    #[inline]
+    fn apply(&self, value: f32) -> u8 {
+        match self {
+            ToneMap::Clamp => value.clamp(0.0, 255.0) as u8,
+            ToneMap::Reinhard => {
+                let v = value.max(0.0) / 255.0;
+                (v / (1.0 + v) * 255.0) as u8
+            }
+        }
+    }
+}
+
+/// 视图约束 - 限制相机的缩放范围和平移范围
+#[derive(Debug, Clone, Copy)]
+struct ViewBounds {
+    /// 最小缩放
+    min_zoom: f32,
+    /// 最大缩放
+    max_zoom: f32,
+    /// 相机位置允许的范围 (x, y, w, h)
+    pan_limit: (f32, f32, f32, f32),
+}
 
 /// 场景 - 管理所有可渲染对象
 pub struct Scene {
@@ -14,10 +72,48 @@ pub struct Scene {
     height: u32,
     /// 背景颜色 (RGBA)
     background_color: [u8; 4],
+    /// 背景图像（覆盖在背景颜色之上）
+    background_image: Option<BackgroundImage>,
+    /// 程序化背景函数（优先级最高）
+    background_fn: Option<Box<dyn Fn(u32, u32) -> u32>>,
+    /// 全局不透明度（0.0-1.0），作用于最终输出的 alpha
+    global_opacity: f32,
+    /// 输出 buffer 是否使用预乘 alpha
+    output_premultiplied: bool,
+    /// 渲染前是否清屏（关闭后在现有 buffer 内容上叠加绘制）
+    clear_enabled: bool,
+    /// 渲染裁剪矩形 (x, y, w, h)，区域外的像素保持不变
+    clip_rect: Option<(u32, u32, u32, u32)>,
+    /// 拾取时的 alpha 阈值，有效 alpha 大于该值才命中
+    pick_alpha_threshold: u8,
     /// 精灵列表
     sprites: Vec<Box<dyn Sprite>>,
+    /// 精灵 ID -> 在 `sprites` 中的下标，增删和排序时同步更新
+    sprite_index: HashMap<u64, usize>,
+    /// 精灵名称（名称 -> 精灵 ID）
+    names: HashMap<String, u64>,
+    /// 父子关系（子精灵 ID -> 父精灵 ID）
+    parents: HashMap<u64, u64>,
     /// 是否需要重新排序
     needs_sort: bool,
+    /// 上次渲染后是否有改动（为 false 时 render 直接复用现有 buffer）
+    needs_render: bool,
+    /// 相机位置（屏幕左上角对应的世界坐标）
+    camera_position: Vec2,
+    /// 相机缩放
+    camera_zoom: f32,
+    /// 相机视图约束
+    view_bounds: Option<ViewBounds>,
+    /// 累积模式的色调映射（`None` 表示未启用累积模式）
+    accumulation: Option<ToneMap>,
+    /// 浮点累积缓冲（RGBA，数值范围 0-255 但不截断）
+    accum_buffer: Vec<f32>,
+    /// 累积模式下单个精灵的临时渲染缓冲
+    scratch_buffer: Vec<u8>,
+    /// 超采样倍数（1 表示关闭）
+    supersample: u32,
+    /// 超采样的高分辨率渲染缓冲，跨帧复用
+    supersample_buffer: Vec<u8>,
 }
 
 impl Scene {
@@ -27,14 +123,35 @@ impl Scene {
     /// * `width` - 场景宽度
     /// * `height` - 场景高度
     pub fn new(width: u32, height: u32) -> Self {
-        let size = (width * height * 4) as usize; // RGBA
+        let size = ImageFormat::Rgba
+            .buffer_size(width, height)
+            .unwrap_or_else(|e| panic!("{e}"));
         Self {
             buffer: vec![0; size],
             width,
             height,
             background_color: [0, 0, 0, 255], // 默认黑色背景
+            background_image: None,
+            background_fn: None,
+            global_opacity: 1.0,
+            output_premultiplied: false,
+            clear_enabled: true,
+            clip_rect: None,
+            pick_alpha_threshold: 0,
             sprites: Vec::new(),
+            sprite_index: HashMap::new(),
+            names: HashMap::new(),
+            parents: HashMap::new(),
             needs_sort: false,
+            needs_render: true,
+            camera_position: Vec2::zero(),
+            camera_zoom: 1.0,
+            view_bounds: None,
+            accumulation: None,
+            accum_buffer: Vec::new(),
+            scratch_buffer: Vec::new(),
+            supersample: 1,
+            supersample_buffer: Vec::new(),
         }
     }
 
@@ -53,12 +170,14 @@ impl Scene {
         self.buffer.as_ptr()
     }
 
-    /// 获取 buffer 长度
+    /// 获取 buffer 长度（字节数，与精灵数量无关，精灵数量见 `sprite_count`）
     pub fn len(&self) -> usize {
         self.buffer.len()
     }
 
-    /// 检查场景是否为空
+    /// 检查场景是否没有精灵
+    ///
+    /// 与 `len` 衡量的对象不同：`len` 返回 buffer 字节数，这里对应 `sprite_count() == 0`
     pub fn is_empty(&self) -> bool {
         self.sprites.is_empty()
     }
@@ -68,35 +187,249 @@ impl Scene {
         &self.buffer
     }
 
+    /// 计算 buffer 的哈希值（FNV-1a，非加密）
+    ///
+    /// JS 端可逐帧比较该值，判断渲染结果是否变化以减少上传
+    pub fn buffer_hash(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        self.buffer.iter().fold(FNV_OFFSET, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
+
+    /// 将当前 buffer 编码为 PNG（RGBA 8 位）
+    ///
+    /// 编码的是最近一次渲染的结果，需先调用 `render`。
+    /// 开启预乘输出时 PNG 中也是预乘颜色。场景尺寸为 0 等无法编码时返回空数组
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let written = encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.buffer));
+        if written.is_err() {
+            return Vec::new();
+        }
+        bytes
+    }
+
+    /// 获取背景颜色（十六进制 0xRRGGBBAA）
+    pub fn background_color(&self) -> u32 {
+        let [r, g, b, a] = self.background_color;
+        pack_rgba(r, g, b, a)
+    }
+
     /// 设置背景颜色
     pub fn set_background_color(&mut self, r: u8, g: u8, b: u8, a: u8) -> &mut Self {
+        self.needs_render = true;
         self.background_color = [r, g, b, a];
         self
     }
 
-    /// 设置背景颜色（十六进制）
+    /// 设置背景颜色（十六进制 0xRRGGBBAA）
+    ///
+    /// 必须包含 alpha 字节：`0xRRGGBB` 会被解释为全透明颜色，
+    /// 不透明颜色可使用 `rgb(r, g, b)` 构造
     pub fn set_background_color_hex(&mut self, color: u32) -> &mut Self {
-        self.background_color = [
-            ((color >> 24) & 0xFF) as u8,
-            ((color >> 16) & 0xFF) as u8,
-            ((color >> 8) & 0xFF) as u8,
-            (color & 0xFF) as u8,
-        ];
+        self.needs_render = true;
+        let (r, g, b, a) = unpack_rgba(color);
+        self.background_color = [r, g, b, a];
+        self
+    }
+
+    /// 设置背景图像
+    ///
+    /// 图像会被拉伸填满整个场景，半透明部分与背景颜色混合。
+    /// 小图放大时，`Nearest` 呈块状，`Bilinear` 更平滑，适合照片背景。
+    /// 数据长度与尺寸、格式不符时返回错误，不修改现有背景。
+    ///
+    /// # Arguments
+    /// * `buffer` - 图像数据
+    /// * `width` - 图像宽度
+    /// * `height` - 图像高度
+    /// * `format` - 图像格式
+    /// * `sampling` - 采样模式
+    pub fn set_background_image(
+        &mut self,
+        buffer: Vec<u8>,
+        width: u32,
+        height: u32,
+        format: ImageFormat,
+        sampling: SamplingMode,
+    ) -> Result<&mut Self, String> {
+        let expected = format.buffer_size(width, height)?;
+        if buffer.len() != expected {
+            return Err(format!(
+                "buffer length {} does not match {}x{} {:?} (expected {})",
+                buffer.len(),
+                width,
+                height,
+                format,
+                expected
+            ));
+        }
+        self.needs_render = true;
+        self.background_image = Some(BackgroundImage {
+            buffer,
+            width,
+            height,
+            format,
+            sampling,
+        });
+        Ok(self)
+    }
+
+    /// 移除背景图像
+    pub fn clear_background_image(&mut self) -> &mut Self {
+        self.needs_render = true;
+        self.background_image = None;
+        self
+    }
+
+    /// 设置程序化背景函数
+    ///
+    /// 清屏时对每个像素调用 `f(x, y)`，返回值为颜色 (0xRRGGBBAA)，
+    /// 可用于网格、点阵等无需图像的背景。设置后优先于背景图像和背景颜色。
+    /// 注意：每帧每个像素都会调用一次闭包，大尺寸场景下开销明显。
+    pub fn set_background_fn(&mut self, f: impl Fn(u32, u32) -> u32 + 'static) -> &mut Self {
+        self.needs_render = true;
+        self.background_fn = Some(Box::new(f));
+        self
+    }
+
+    /// 移除程序化背景函数
+    pub fn clear_background_fn(&mut self) -> &mut Self {
+        self.needs_render = true;
+        self.background_fn = None;
+        self
+    }
+
+    /// 设置全局不透明度（0.0-1.0，超出范围会被钳制，非有限值会被忽略）
+    ///
+    /// 渲染结束时统一乘到输出 buffer 的 alpha 上，
+    /// 用于整个场景的淡入淡出，比逐个设置精灵更高效
+    pub fn set_global_opacity(&mut self, opacity: f32) -> &mut Self {
+        if opacity.is_finite() {
+            self.needs_render = true;
+            self.global_opacity = opacity.clamp(0.0, 1.0);
+        }
+        self
+    }
+
+    /// 获取全局不透明度
+    pub fn global_opacity(&self) -> f32 {
+        self.global_opacity
+    }
+
+    /// 设置输出 buffer 是否使用预乘 alpha（默认否）
+    ///
+    /// `putImageData` 需要非预乘的 RGBA；WebGL 纹理等场景通常需要预乘。
+    /// 只在 `render` 写出最终结果时转换，内部合成始终使用非预乘颜色
+    pub fn set_output_premultiplied(&mut self, premultiplied: bool) -> &mut Self {
+        self.needs_render = true;
+        self.output_premultiplied = premultiplied;
+        self
+    }
+
+    /// 输出 buffer 是否使用预乘 alpha
+    pub fn output_premultiplied(&self) -> bool {
+        self.output_premultiplied
+    }
+
+    /// 设置 `render` 前是否清屏（默认是）
+    ///
+    /// 关闭后不绘制背景，精灵直接合成到 buffer 现有内容之上，用于叠加层渲染
+    pub fn set_clear_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.needs_render = true;
+        self.clear_enabled = enabled;
         self
     }
 
+    /// `render` 前是否清屏
+    pub fn clear_enabled(&self) -> bool {
+        self.clear_enabled
+    }
+
+    /// 设置渲染裁剪矩形 (x, y, w, h)，`None` 表示整个 buffer
+    ///
+    /// 清屏、精灵合成及全局不透明度等后处理都只作用于裁剪区域，区域外的像素保持不变，
+    /// 可用于滚动面板或局部重绘
+    pub fn set_clip_rect(&mut self, rect: Option<(u32, u32, u32, u32)>) -> &mut Self {
+        self.needs_render = true;
+        self.clip_rect = rect;
+        self
+    }
+
+    /// 获取渲染裁剪矩形
+    pub fn clip_rect(&self) -> Option<(u32, u32, u32, u32)> {
+        self.clip_rect
+    }
+
+    /// 裁剪矩形限制到 `width x height` 范围后的 (x0, y0, x1, y1)，右、下边界不包含
+    fn clip_bounds(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let clip = self.clip_rect.unwrap_or((0, 0, width, height));
+        clip_region(clip, width, height)
+    }
+
+    /// 按行遍历 `width x height` 的 buffer 中裁剪区域内的像素
+    fn clipped_rows<'a>(
+        &self,
+        buffer: &'a mut [u8],
+        width: u32,
+        height: u32,
+    ) -> impl Iterator<Item = &'a mut [u8]> + use<'a> {
+        let (x0, y0, x1, y1) = self.clip_bounds(width, height);
+        let stride = (width as usize * 4).max(1);
+        buffer
+            .chunks_exact_mut(stride)
+            .skip(y0 as usize)
+            .take((y1 - y0) as usize)
+            .map(move |row| &mut row[x0 as usize * 4..x1 as usize * 4])
+    }
+
     /// 添加精灵到场景
     pub fn add<S: Sprite + 'static>(&mut self, sprite: S) -> u64 {
+        self.needs_render = true;
         let id = sprite.id();
+        self.sprite_index.insert(id, self.sprites.len());
         self.sprites.push(Box::new(sprite));
         self.needs_sort = true;
         id
     }
 
+    /// 添加带名称的精灵
+    ///
+    /// 名称已被占用时覆盖：名称改为指向新精灵，原精灵仍保留在场景中但不再有名称
+    pub fn add_named<S: Sprite + 'static>(&mut self, sprite: S, name: &str) -> u64 {
+        let id = self.add(sprite);
+        self.names.insert(name.to_string(), id);
+        id
+    }
+
+    /// 通过名称获取精灵可变引用，与 `get_sprite_mut` 一样视为场景已改动
+    pub fn get_sprite_by_name(&mut self, name: &str) -> Option<&mut Box<dyn Sprite>> {
+        let id = *self.names.get(name)?;
+        self.get_sprite_mut(id)
+    }
+
     /// 移除精灵
     pub fn remove(&mut self, id: u64) -> bool {
-        if let Some(pos) = self.sprites.iter().position(|s| s.id() == id) {
+        self.needs_render = true;
+        if let Some(pos) = self.sprite_index.remove(&id) {
             self.sprites.remove(pos);
+            // 之后的精灵整体前移一位
+            for (i, sprite) in self.sprites.iter().enumerate().skip(pos) {
+                self.sprite_index.insert(sprite.id(), i);
+            }
+            // 子精灵脱离父级，保留各自的本地变换
+            self.parents.remove(&id);
+            self.parents.retain(|_, parent| *parent != id);
+            self.names.retain(|_, named| *named != id);
             true
         } else {
             false
@@ -109,142 +442,1218 @@ impl Scene {
     }
 
     /// 获取精灵可变引用（通过 ID）
+    ///
+    /// 无法追踪通过引用做的修改，因此调用即视为场景已改动，下次 `render` 会重新渲染
     pub fn get_sprite_mut(&mut self, id: u64) -> Option<&mut Box<dyn Sprite>> {
-        self.sprites.iter_mut().find(|s| s.id() == id)
+        self.needs_render = true;
+        self.find_sprite_mut(id)
+    }
+
+    /// 获取精灵只读引用（通过 ID），不会标记场景改动
+    pub fn get_sprite(&self, id: u64) -> Option<&dyn Sprite> {
+        self.index_of(id).map(|i| self.sprites[i].as_ref())
+    }
+
+    /// 场景中是否存在指定 ID 的精灵
+    pub fn contains(&self, id: u64) -> bool {
+        self.index_of(id).is_some()
+    }
+
+    /// 按渲染顺序列出所有精灵 ID
+    pub fn sprite_ids(&self) -> Vec<u64> {
+        self.render_order()
+            .into_iter()
+            .map(|i| self.sprites[i].id())
+            .collect()
+    }
+
+    /// 查找精灵（只读用途，不标记改动）
+    fn find_sprite_mut(&mut self, id: u64) -> Option<&mut Box<dyn Sprite>> {
+        let index = self.index_of(id)?;
+        Some(&mut self.sprites[index])
+    }
+
+    /// 查找精灵在列表中的位置
+    fn index_of(&self, id: u64) -> Option<usize> {
+        self.sprite_index.get(&id).copied()
+    }
+
+    /// 按当前列表顺序重建 ID 索引
+    fn rebuild_sprite_index(&mut self) {
+        self.sprite_index.clear();
+        for (i, sprite) in self.sprites.iter().enumerate() {
+            self.sprite_index.insert(sprite.id(), i);
+        }
+    }
+
+    /// 设置精灵的父级，`None` 表示解除父子关系
+    ///
+    /// 子精灵的变换相对于父精灵的位置、旋转、缩放（不含锚点偏移），
+    /// 移动父精灵时子精灵随之移动。任一精灵不存在或会形成环时返回 false
+    pub fn set_parent(&mut self, child: u64, parent: Option<u64>) -> bool {
+        if self.index_of(child).is_none() {
+            return false;
+        }
+        match parent {
+            Some(parent) => {
+                if self.index_of(parent).is_none() || self.is_ancestor_or_self(child, parent) {
+                    return false;
+                }
+                self.parents.insert(child, parent);
+            }
+            None => {
+                self.parents.remove(&child);
+            }
+        }
+        self.needs_render = true;
+        self.needs_sort = true;
+        true
+    }
+
+    /// 获取精灵的父级
+    pub fn parent(&self, child: u64) -> Option<u64> {
+        self.parents.get(&child).copied()
+    }
+
+    /// `ancestor` 是否为 `id` 本身或其祖先
+    fn is_ancestor_or_self(&self, ancestor: u64, id: u64) -> bool {
+        let mut current = Some(id);
+        while let Some(node) = current {
+            if node == ancestor {
+                return true;
+            }
+            current = self.parents.get(&node).copied();
+        }
+        false
+    }
+
+    /// 计算精灵的世界变换矩阵（本地矩阵依次左乘父级链上的变换）
+    fn world_matrix(&mut self, index: usize) -> Matrix3x3 {
+        let mut matrix = self.sprites[index].get_transform_matrix();
+        let mut parent = self.parents.get(&self.sprites[index].id()).copied();
+        while let Some(id) = parent {
+            let Some(parent_index) = self.index_of(id) else {
+                break;
+            };
+            matrix = self.sprites[parent_index]
+                .transform_mut()
+                .matrix()
+                .multiply(&matrix);
+            parent = self.parents.get(&id).copied();
+        }
+        matrix
+    }
+
+    /// 获取精灵尺寸和世界变换矩阵
+    fn sprite_world_geometry(&mut self, id: u64) -> Option<(f32, f32, Matrix3x3)> {
+        let index = self.index_of(id)?;
+        let sprite = &self.sprites[index];
+        let (w, h) = (sprite.width() as f32, sprite.height() as f32);
+        Some((w, h, self.world_matrix(index)))
+    }
+
+    /// 获取精灵在世界坐标下的轴对齐包围盒 (min, max)
+    pub fn world_bounds(&mut self, id: u64) -> Option<(Vec2, Vec2)> {
+        let (w, h, matrix) = self.sprite_world_geometry(id)?;
+        Some(transformed_bounds(&matrix, w, h))
+    }
+
+    /// 获取精灵四个角点的屏幕坐标（顺序为 (0,0)、(w,0)、(w,h)、(0,h)）
+    ///
+    /// 包含父级链和相机变换，与渲染结果一致，可用于绘制选框；只读查询，不标记改动
+    pub fn screen_corners(&mut self, id: u64) -> Option<[Vec2; 4]> {
+        let view = self.view_matrix();
+        let (w, h, matrix) = self.sprite_world_geometry(id)?;
+        Some(transformed_corners(&view.multiply(&matrix), w, h))
+    }
+
+    /// 获取所有精灵世界包围盒的并集 (min, max)，场景为空时返回 `None`
+    ///
+    /// 可用于"适应内容"时框定相机
+    pub fn content_bounds(&mut self) -> Option<(Vec2, Vec2)> {
+        let ids: Vec<u64> = self.sprites.iter().map(|s| s.id()).collect();
+        ids.into_iter()
+            .filter_map(|id| self.world_bounds(id))
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(&min_b), max_a.max(&max_b)))
+    }
+
+    /// 判断精灵是否实际出现在屏幕上
+    ///
+    /// 使用精灵经相机变换后的包围盒与场景矩形求交，
+    /// 只反映几何位置，与精灵自身的显示开关无关
+    pub fn is_sprite_visible_on_screen(&mut self, id: u64) -> bool {
+        let view = self.view_matrix();
+        let (width, height) = (self.width as f32, self.height as f32);
+        let Some((w, h, matrix)) = self.sprite_world_geometry(id) else {
+            return false;
+        };
+
+        let (min, max) = transformed_bounds(&view.multiply(&matrix), w, h);
+
+        min.x < width
+            && max.x > 0.0
+            && min.y < height
+            && max.y > 0.0
+            && min.x < max.x
+            && min.y < max.y
+    }
+
+    /// 拾取屏幕坐标处最上层的精灵，返回其 ID
+    ///
+    /// 按与 `render` 相同的顺序从上往下查找，将坐标逆变换到精灵本地空间，
+    /// 落在精灵范围内且该处有效 alpha（像素 alpha 乘以不透明度和着色 alpha）
+    /// 大于拾取阈值时命中。坐标会经过相机变换
+    pub fn pick(&mut self, x: f32, y: f32) -> Option<u64> {
+        self.sort_sprites();
+        let view = self.view_matrix();
+        let point = Vec2::new(x, y);
+
+        for index in (0..self.sprites.len()).rev() {
+            let Some(inverse) = view.multiply(&self.world_matrix(index)).inverse() else {
+                continue;
+            };
+            let sprite = &self.sprites[index];
+            let (w, h) = (sprite.width(), sprite.height());
+            let local = inverse.transform_point(point);
+            let inside =
+                local.x >= 0.0 && local.y >= 0.0 && local.x < w as f32 && local.y < h as f32;
+            if !inside {
+                continue;
+            }
+            let alpha = sprite.alpha_at(local.x as u32, local.y as u32) as f32
+                * sprite.opacity()
+                * sprite.tint()[3];
+            if alpha.round() > self.pick_alpha_threshold as f32 {
+                return Some(sprite.id());
+            }
+        }
+        None
+    }
+
+    /// 设置拾取的 alpha 阈值（默认 0，即任何非透明像素都命中）
+    ///
+    /// 提高阈值可以让半透明的阴影、光晕等不挡住下层精灵
+    pub fn set_pick_alpha_threshold(&mut self, threshold: u8) -> &mut Self {
+        self.pick_alpha_threshold = threshold;
+        self
+    }
+
+    /// 获取拾取的 alpha 阈值
+    pub fn pick_alpha_threshold(&self) -> u8 {
+        self.pick_alpha_threshold
+    }
+
+    /// 估算精灵在屏幕上覆盖的像素数
+    ///
+    /// 取精灵经相机变换后的包围盒与场景矩形的交集面积，轴对齐精灵为精确值，
+    /// 旋转精灵会偏大。可用于按覆盖面积切换细节层级（LOD）。精灵不存在时返回 0
+    pub fn sprite_screen_area(&mut self, id: u64) -> u32 {
+        let view = self.view_matrix();
+        let (width, height) = (self.width as f32, self.height as f32);
+        let Some((w, h, matrix)) = self.sprite_world_geometry(id) else {
+            return 0;
+        };
+
+        let (min, max) = transformed_bounds(&view.multiply(&matrix), w, h);
+
+        let visible_w = (max.x.min(width) - min.x.max(0.0)).max(0.0);
+        let visible_h = (max.y.min(height) - min.y.max(0.0)).max(0.0);
+        (visible_w * visible_h).round() as u32
     }
 
     /// 清空所有精灵
     pub fn clear(&mut self) {
+        self.needs_render = true;
         self.sprites.clear();
+        self.sprite_index.clear();
+        self.names.clear();
+        self.parents.clear();
         self.needs_sort = false;
     }
 
     /// 调整场景尺寸
     pub fn resize(&mut self, width: u32, height: u32) {
+        let size = ImageFormat::Rgba
+            .buffer_size(width, height)
+            .unwrap_or_else(|e| panic!("{e}"));
+        self.needs_render = true;
         self.width = width;
         self.height = height;
-        self.buffer.resize((width * height * 4) as usize, 0);
+        self.buffer.resize(size, 0);
     }
 
-    /// 按 z-order 排序精灵（稳定排序保持添加顺序）
-    fn sort_sprites(&mut self) {
-        if self.needs_sort {
-            self.sprites.sort_by_key(|s| s.z_order());
-            self.needs_sort = false;
-        }
+    // ===== 相机 =====
+
+    /// 获取相机位置
+    pub fn camera_position(&self) -> Vec2 {
+        self.camera_position
     }
 
-    /// 清空 buffer（填充背景色）
-    fn clear_buffer(&mut self) {
-        let [r, g, b, a] = self.background_color;
-        for i in (0..self.buffer.len()).step_by(4) {
-            self.buffer[i] = r;
-            self.buffer[i + 1] = g;
-            self.buffer[i + 2] = b;
-            self.buffer[i + 3] = a;
+    /// 获取相机缩放
+    pub fn camera_zoom(&self) -> f32 {
+        self.camera_zoom
+    }
+
+    /// 设置相机位置（受视图约束限制）
+    pub fn set_camera_position(&mut self, x: f32, y: f32) -> &mut Self {
+        self.camera_position = Vec2::new(x, y);
+        self.clamp_camera();
+        self
+    }
+
+    /// 设置相机缩放（受视图约束限制，非正值会被忽略）
+    pub fn set_camera_zoom(&mut self, zoom: f32) -> &mut Self {
+        if zoom > 0.0 {
+            self.camera_zoom = zoom;
+            self.clamp_camera();
         }
+        self
     }
 
-    /// 渲染场景
-    ///
-    /// 按 z-order 从小到大顺序渲染所有精灵
-    pub fn render(&mut self) {
-        // 排序精灵
-        self.sort_sprites();
+    /// 平移相机（世界坐标增量）
+    pub fn pan(&mut self, dx: f32, dy: f32) -> &mut Self {
+        let position = self.camera_position;
+        self.set_camera_position(position.x + dx, position.y + dy)
+    }
 
-        // 清空 buffer
-        self.clear_buffer();
+    /// 缩放相机（乘法）
+    pub fn zoom(&mut self, factor: f32) -> &mut Self {
+        let zoom = self.camera_zoom * factor;
+        self.set_camera_zoom(zoom)
+    }
+
+    /// 设置视图约束
+    ///
+    /// 之后的平移、缩放都会被限制在约束内，避免整个场景被移出视野
+    ///
+    /// # Arguments
+    /// * `min_zoom` - 最小缩放
+    /// * `max_zoom` - 最大缩放
+    /// * `pan_limit_rect` - 相机位置允许的范围 (x, y, w, h)
+    ///
+    /// 任一参数为非有限值时忽略本次设置，保留原有约束
+    pub fn set_view_bounds(
+        &mut self,
+        min_zoom: f32,
+        max_zoom: f32,
+        pan_limit_rect: (f32, f32, f32, f32),
+    ) -> &mut Self {
+        let (x, y, w, h) = pan_limit_rect;
+        if ![min_zoom, max_zoom, x, y, w, h]
+            .iter()
+            .all(|v| v.is_finite())
+        {
+            return self;
+        }
+        self.view_bounds = Some(ViewBounds {
+            min_zoom: min_zoom.min(max_zoom),
+            max_zoom: max_zoom.max(min_zoom),
+            pan_limit: pan_limit_rect,
+        });
+        self.clamp_camera();
+        self
+    }
 
-        // 按顺序渲染精灵
-        let width = self.width;
-        let height = self.height;
+    /// 移除视图约束
+    pub fn clear_view_bounds(&mut self) -> &mut Self {
+        self.needs_render = true;
+        self.view_bounds = None;
+        self
+    }
 
-        // 使用 unsafe 来绕过借用检查，因为我们需要同时访问 sprites 和 buffer
-        // 这里是安全的，因为 sprite.render_to 只会修改 buffer，不会修改 sprites
-        let buffer_ptr = self.buffer.as_mut_ptr();
-        let buffer_len = self.buffer.len();
+    /// 获取视图矩阵（世界坐标 -> 屏幕坐标）
+    ///
+    /// 先减去相机位置，再按相机缩放
+    pub fn view_matrix(&self) -> Matrix3x3 {
+        let scale = Matrix3x3::scaling(self.camera_zoom, self.camera_zoom);
+        let translate = Matrix3x3::translation(-self.camera_position.x, -self.camera_position.y);
+        scale.multiply(&translate)
+    }
 
-        for sprite in self.sprites.iter_mut() {
-            let buffer_slice = unsafe { std::slice::from_raw_parts_mut(buffer_ptr, buffer_len) };
-            sprite.render_to(buffer_slice, width, height);
+    /// 将相机限制在视图约束内
+    fn clamp_camera(&mut self) {
+        self.needs_render = true;
+        if let Some(bounds) = self.view_bounds {
+            let (x, y, w, h) = bounds.pan_limit;
+            self.camera_zoom = self.camera_zoom.clamp(bounds.min_zoom, bounds.max_zoom);
+            self.camera_position.x = self.camera_position.x.clamp(x, x + w.max(0.0));
+            self.camera_position.y = self.camera_position.y.clamp(y, y + h.max(0.0));
         }
     }
 
-    /// 标记需要重新排序（当精灵 z-order 改变时调用）
-    pub fn mark_needs_sort(&mut self) {
-        self.needs_sort = true;
+    // ===== 累积模式 =====
+
+    /// 启用浮点累积模式
+    ///
+    /// 精灵先合成到 `f32` 缓冲中，叠加混合的精灵相加时不会截断，
+    /// 渲染结束时再通过色调映射写回 8 位 buffer。适合粒子发光等效果。
+    /// 注意：每个精灵都会额外渲染到一块整屏临时缓冲，开销高于普通模式。
+    pub fn enable_accumulation(&mut self, tone_map: ToneMap) -> &mut Self {
+        self.needs_render = true;
+        self.accumulation = Some(tone_map);
+        self
     }
-}
 
-/// 为 Scene 实现 Debug trait
-impl std::fmt::Debug for Scene {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Scene")
-            .field("width", &self.width)
-            .field("height", &self.height)
-            .field("sprite_count", &self.sprites.len())
-            .field("background_color", &self.background_color)
-            .finish()
+    /// 关闭累积模式并释放累积缓冲
+    pub fn disable_accumulation(&mut self) -> &mut Self {
+        self.needs_render = true;
+        self.accumulation = None;
+        self.accum_buffer = Vec::new();
+        self.scratch_buffer = Vec::new();
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::core::format::ImageFormat;
-    use crate::scene::sprite::ImageSprite;
+    /// 获取累积缓冲（仅累积模式下渲染后可用）
+    pub fn accumulation_buffer(&self) -> Option<&[f32]> {
+        match self.accumulation {
+            Some(_) => Some(&self.accum_buffer),
+            None => None,
+        }
+    }
 
-    #[test]
-    fn test_create_scene() {
-        let scene = Scene::new(800, 600);
-        assert_eq!(scene.width(), 800);
-        assert_eq!(scene.height(), 600);
-        assert_eq!(scene.buffer().len(), 800 * 600 * 4);
+    /// 累积模式渲染
+    ///
+    /// 每个精灵先渲染到透明的临时缓冲（得到预乘 alpha 的颜色），
+    /// 再按混合模式累加到浮点缓冲，最后色调映射回 8 位 buffer
+    fn render_accumulated(
+        &mut self,
+        buffer: &mut [u8],
+        (width, height): (u32, u32),
+        tone_map: ToneMap,
+        only: Option<&[u64]>,
+    ) {
+        let len = buffer.len();
+        let view = self.view_matrix();
+        let clip = self.clip_rect;
+
+        self.accum_buffer.clear();
+        self.accum_buffer.extend(buffer.iter().map(|&v| v as f32));
+        self.scratch_buffer.resize(len, 0);
+
+        for index in 0..self.sprites.len() {
+            if !self.is_selected(index, only) {
+                continue;
+            }
+            self.scratch_buffer.fill(0);
+            let matrix = view.multiply(&self.world_matrix(index));
+            let sprite = &mut self.sprites[index];
+            // 以普通混合绘制到透明临时缓冲得到预乘颜色，精灵自身的混合模式在累积时套用
+            let mode = sprite.blend_mode();
+            if let Some(image) = sprite.as_image_sprite_mut() {
+                image.set_blend_mode(BlendMode::Normal);
+            }
+            sprite.render_with_matrix_clipped(
+                &matrix,
+                &mut self.scratch_buffer,
+                width,
+                height,
+                clip,
+            );
+            if let Some(image) = sprite.as_image_sprite_mut() {
+                image.set_blend_mode(mode);
+            }
+
+            for (acc, src) in self
+                .accum_buffer
+                .chunks_exact_mut(4)
+                .zip(self.scratch_buffer.chunks_exact(4))
+            {
+                let alpha = src[3] as f32 / 255.0;
+                if alpha <= 0.0 {
+                    continue;
+                }
+                match mode {
+                    BlendMode::Additive => {
+                        for c in 0..3 {
+                            acc[c] += src[c] as f32;
+                        }
+                        acc[3] = (acc[3] + src[3] as f32).min(255.0);
+                    }
+                    BlendMode::Multiply | BlendMode::Screen => {
+                        // 临时缓冲中是预乘颜色，先还原再套用公式
+                        let inv_alpha = 1.0 - alpha;
+                        for c in 0..3 {
+                            let blended = mode.blend_channel(src[c] as f32 / alpha, acc[c]);
+                            acc[c] = blended * alpha + acc[c] * inv_alpha;
+                        }
+                        acc[3] = src[3] as f32 + acc[3] * inv_alpha;
+                    }
+                    BlendMode::Normal => {
+                        let inv_alpha = 1.0 - alpha;
+                        for c in 0..3 {
+                            acc[c] = src[c] as f32 + acc[c] * inv_alpha;
+                        }
+                        acc[3] = src[3] as f32 + acc[3] * inv_alpha;
+                    }
+                }
+            }
+        }
+
+        // 颜色通道做色调映射，Alpha 通道仅截断；裁剪区域外保持原样
+        let (x0, y0, x1, y1) = self.clip_bounds(width, height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let idx = ((y * width + x) * 4) as usize;
+                let acc = &self.accum_buffer[idx..idx + 4];
+                let dst = &mut buffer[idx..idx + 4];
+                for c in 0..3 {
+                    dst[c] = tone_map.apply(acc[c]);
+                }
+                dst[3] = acc[3].clamp(0.0, 255.0) as u8;
+            }
+        }
     }
 
-    #[test]
-    fn test_add_remove_sprite() {
-        let mut scene = Scene::new(100, 100);
-        let sprite = ImageSprite::new(50, 50, ImageFormat::Rgba);
-        let id = scene.add(sprite);
+    // ===== 超采样 =====
 
-        assert_eq!(scene.sprite_count(), 1);
+    /// 设置超采样倍数（1-4，1 表示关闭）
+    ///
+    /// 精灵先以 `factor` 倍分辨率渲染，再按 `factor x factor` 块取平均缩回，
+    /// 可平滑旋转、缩放精灵的锯齿边缘。高分辨率缓冲跨帧复用，
+    /// 只在场景尺寸或倍数变化时重新分配。
+    /// 注意：超采样时精灵的裁剪矩形按高分辨率坐标解释；累积模式下不生效。
+    pub fn set_supersample(&mut self, factor: u32) -> &mut Self {
+        self.needs_render = true;
+        self.supersample = factor.clamp(1, 4);
+        if self.supersample == 1 {
+            self.supersample_buffer = Vec::new();
+        }
+        self
+    }
 
-        let removed = scene.remove(id);
-        assert!(removed);
-        assert_eq!(scene.sprite_count(), 0);
+    /// 获取超采样倍数
+    pub fn supersample(&self) -> u32 {
+        self.supersample
     }
 
-    #[test]
-    fn test_z_order_sorting() {
-        let mut scene = Scene::new(100, 100);
+    /// 超采样渲染：放大背景、高分辨率合成精灵，再盒式下采样回 buffer
+    fn render_supersampled(
+        &mut self,
+        buffer: &mut [u8],
+        (width, height): (u32, u32),
+        only: Option<&[u64]>,
+    ) {
+        let factor = self.supersample;
+        let (ss_width, ss_height) = (width * factor, height * factor);
+        let f = factor as usize;
 
-        let mut sprite1 = ImageSprite::new(10, 10, ImageFormat::Rgba);
-        sprite1.set_z_order(10);
+        // 取出复用的缓冲，尺寸不变时不会重新分配
+        let mut ss = std::mem::take(&mut self.supersample_buffer);
+        ss.resize(ss_width as usize * ss_height as usize * 4, 0);
 
-        let mut sprite2 = ImageSprite::new(10, 10, ImageFormat::Rgba);
-        sprite2.set_z_order(5);
+        // 背景已绘制到 buffer，按最近邻放大
+        for (y, row) in ss.chunks_exact_mut(ss_width as usize * 4).enumerate() {
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let src = ((y / f) * width as usize + x / f) * 4;
+                pixel.copy_from_slice(&buffer[src..src + 4]);
+            }
+        }
 
-        let mut sprite3 = ImageSprite::new(10, 10, ImageFormat::Rgba);
-        sprite3.set_z_order(15);
+        let view = Matrix3x3::scaling(factor as f32, factor as f32).multiply(&self.view_matrix());
+        let clip = self.clip_rect.map(|(x, y, w, h)| {
+            let scale = |v: u32| v.saturating_mul(factor);
+            (scale(x), scale(y), scale(w), scale(h))
+        });
+        for index in 0..self.sprites.len() {
+            if !self.is_selected(index, only) {
+                continue;
+            }
+            let matrix = view.multiply(&self.world_matrix(index));
+            self.sprites[index]
+                .render_with_matrix_clipped(&matrix, &mut ss, ss_width, ss_height, clip);
+        }
 
-        scene.add(sprite1);
-        scene.add(sprite2);
-        scene.add(sprite3);
+        // 盒式下采样（只处理裁剪区域）
+        let samples = (f * f) as u32;
+        let (x0, y0, x1, y1) = self.clip_bounds(width, height);
+        for y in y0 as usize..y1 as usize {
+            for x in x0 as usize..x1 as usize {
+                let mut sum = [0u32; 4];
+                for sy in y * f..(y + 1) * f {
+                    for sx in x * f..(x + 1) * f {
+                        let idx = (sy * ss_width as usize + sx) * 4;
+                        for (acc, &v) in sum.iter_mut().zip(&ss[idx..idx + 4]) {
+                            *acc += v as u32;
+                        }
+                    }
+                }
+                let dst = (y * width as usize + x) * 4;
+                for (out, acc) in buffer[dst..dst + 4].iter_mut().zip(sum) {
+                    *out = ((acc + samples / 2) / samples) as u8;
+                }
+            }
+        }
 
-        // 渲染触发排序
-        scene.render();
+        self.supersample_buffer = ss;
+    }
 
-        // 验证排序结果：z_order 5, 10, 15
-        let z_orders: Vec<i32> = scene.sprites.iter().map(|s| s.z_order()).collect();
-        assert_eq!(z_orders, vec![5, 10, 15]);
+    /// 按 z-order 排序精灵，z-order 相同时按层内索引（稳定排序保持添加顺序）
+    ///
+    /// 有父子关系时，排在父精灵之前的子精灵会被移到父精灵之后
+    fn sort_sprites(&mut self) {
+        if self.needs_sort {
+            let order = self.render_order();
+            let mut slots: Vec<Option<Box<dyn Sprite>>> = std::mem::take(&mut self.sprites)
+                .into_iter()
+                .map(Some)
+                .collect();
+            self.sprites = order.into_iter().filter_map(|i| slots[i].take()).collect();
+            self.rebuild_sprite_index();
+            self.needs_sort = false;
+        }
     }
 
-    #[test]
-    fn test_background_color() {
-        let mut scene = Scene::new(2, 2);
-        scene.set_background_color(255, 0, 0, 255);
-        scene.render();
+    /// 计算渲染顺序，返回精灵在列表中的下标，不修改列表
+    fn render_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.sprites.len()).collect();
+        if !self.needs_sort {
+            return order;
+        }
+
+        order.sort_by_key(|&i| (self.sprites[i].z_order(), self.sprites[i].layer_index()));
+        if self.parents.is_empty() {
+            return order;
+        }
+
+        // 保证子精灵位于父精灵之后，其余顺序不变
+        let id = |i: usize| self.sprites[i].id();
+        let mut ordered = Vec::with_capacity(order.len());
+        let mut emitted = HashSet::new();
+        let mut pending = Vec::new();
+
+        for i in order {
+            let ready = self
+                .parents
+                .get(&id(i))
+                .is_none_or(|parent| emitted.contains(parent));
+            if !ready {
+                pending.push(i);
+                continue;
+            }
+            emitted.insert(id(i));
+            ordered.push(i);
+
+            // 父精灵就位后，依次放入等待中的子精灵
+            while let Some(pos) = pending
+                .iter()
+                .position(|&c| emitted.contains(&self.parents[&id(c)]))
+            {
+                let child = pending.remove(pos);
+                emitted.insert(id(child));
+                ordered.push(child);
+            }
+        }
+        ordered.extend(pending);
+        ordered
+    }
+
+    /// 清空 buffer（填充背景色，有背景图像时再绘制背景图像）
+    ///
+    /// 设置了程序化背景函数时只使用该函数
+    fn clear_buffer(&self, buffer: &mut [u8], (width, height): (u32, u32)) {
+        let (x0, y0, x1, y1) = self.clip_bounds(width, height);
+        if let Some(background_fn) = &self.background_fn {
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let (r, g, b, a) = unpack_rgba(background_fn(x, y));
+                    let idx = ((y * width + x) * 4) as usize;
+                    buffer[idx..idx + 4].copy_from_slice(&[r, g, b, a]);
+                }
+            }
+            return;
+        }
+
+        let color = self.background_color;
+        for row in self.clipped_rows(buffer, width, height) {
+            for pixel in row.chunks_exact_mut(4) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+
+        if let Some(image) = &self.background_image {
+            if image.width == 0 || image.height == 0 {
+                return;
+            }
+
+            let channels = image.format as usize;
+            let scale_x = image.width as f32 / width as f32;
+            let scale_y = image.height as f32 / height as f32;
+            let mut texel = [0u8; 4];
+
+            for y in y0..y1 {
+                let sy = (y as f32 + 0.5) * scale_y;
+                for x in x0..x1 {
+                    let sx = (x as f32 + 0.5) * scale_x;
+                    sample(
+                        &image.buffer,
+                        image.width,
+                        image.height,
+                        channels,
+                        sx,
+                        sy,
+                        image.sampling,
+                        &mut texel,
+                    );
+                    let pixel = match image.format {
+                        ImageFormat::Rgba => texel,
+                        ImageFormat::Rgb => [texel[0], texel[1], texel[2], 255],
+                        ImageFormat::Grayscale => [texel[0], texel[0], texel[0], 255],
+                    };
+                    let idx = ((y * width + x) * 4) as usize;
+                    blend_pixel(&mut buffer[idx..idx + 4], pixel, BlendMode::Normal);
+                }
+            }
+        }
+    }
+
+    /// 有改动时渲染，返回是否实际进行了渲染
+    ///
+    /// 改动追踪与 `render` 相同：增删精灵、背景/相机变化、`get_sprite_mut` 等都会标记改动
+    pub fn render_if_dirty(&mut self) -> bool {
+        let dirty = self.needs_render;
+        self.render();
+        dirty
+    }
+
+    /// 渲染场景
+    ///
+    /// 按 z-order 从小到大顺序渲染所有精灵。
+    /// 上次渲染后场景没有改动时直接返回，保留现有 buffer。
+    pub fn render(&mut self) {
+        if !self.needs_render {
+            return;
+        }
+
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let (width, height) = (self.width, self.height);
+        let result = self.render_into(&mut buffer, width, height);
+        self.buffer = buffer;
+        debug_assert!(result.is_ok(), "场景 buffer 与尺寸不一致");
+        self.needs_render = false;
+    }
+
+    /// 渲染到调用方提供的 RGBA buffer（如双缓冲或大图集中的一块区域）
+    ///
+    /// 按 `width x height` 渲染，背景、相机、裁剪矩形等设置与 `render` 相同；
+    /// 总是重新渲染，不影响场景自身 buffer 和改动追踪。
+    /// `target` 长度不足 `width * height * 4` 时返回错误，多余部分保持不变
+    pub fn render_into(
+        &mut self,
+        target: &mut [u8],
+        width: u32,
+        height: u32,
+    ) -> Result<(), String> {
+        let size = ImageFormat::Rgba.buffer_size(width, height)?;
+        if target.len() < size {
+            return Err(format!(
+                "target buffer length {} is too small for {}x{} Rgba (expected at least {})",
+                target.len(),
+                width,
+                height,
+                size
+            ));
+        }
+        self.render_frame(&mut target[..size], (width, height), None);
+        Ok(())
+    }
+
+    /// 渲染一帧到尺寸为 `size` (宽, 高) 的 `buffer`
+    ///
+    /// `only` 为 `Some` 时只合成列表中的精灵，清屏和后处理与完整渲染相同
+    fn render_frame(&mut self, buffer: &mut [u8], size: (u32, u32), only: Option<&[u64]>) {
+        let (width, height) = size;
+        // 排序精灵
+        self.sort_sprites();
+
+        // 清空 buffer
+        if self.clear_enabled {
+            self.clear_buffer(buffer, size);
+        }
+
+        match self.accumulation {
+            Some(tone_map) => self.render_accumulated(buffer, size, tone_map, only),
+            None if self.supersample > 1 => self.render_supersampled(buffer, size, only),
+            None => self.composite_sprites(buffer, size, only),
+        }
+
+        // 应用全局不透明度
+        if self.global_opacity < 1.0 {
+            let opacity = self.global_opacity;
+            for pixel in self
+                .clipped_rows(buffer, width, height)
+                .flat_map(|row| row.chunks_exact_mut(4))
+            {
+                pixel[3] = (pixel[3] as f32 * opacity).round() as u8;
+            }
+        }
+
+        // 按需转换为预乘 alpha
+        if self.output_premultiplied {
+            for pixel in self
+                .clipped_rows(buffer, width, height)
+                .flat_map(|row| row.chunks_exact_mut(4))
+            {
+                let alpha = pixel[3] as u32;
+                for c in &mut pixel[..3] {
+                    *c = ((*c as u32 * alpha + 127) / 255) as u8;
+                }
+            }
+        }
+    }
+
+    /// 只渲染指定 ID 的精灵
+    ///
+    /// 与 `render` 走同一渲染流程（清屏、累积/超采样、全局不透明度、预乘输出），
+    /// 只是跳过不在列表中的精灵，可用于选中项高亮、"幽灵"预览等。
+    pub fn render_only(&mut self, ids: &[u64]) {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let size = (self.width, self.height);
+        self.render_frame(&mut buffer, size, Some(ids));
+        self.buffer = buffer;
+        // buffer 不再是完整的渲染结果
+        self.needs_render = true;
+    }
+
+    /// 精灵是否参与本次合成
+    fn is_selected(&self, index: usize, only: Option<&[u64]>) -> bool {
+        only.is_none_or(|ids| ids.contains(&self.sprites[index].id()))
+    }
+
+    /// 按顺序将精灵合成到 buffer，`only` 为 `Some` 时只合成列表中的精灵
+    fn composite_sprites(
+        &mut self,
+        buffer: &mut [u8],
+        (width, height): (u32, u32),
+        only: Option<&[u64]>,
+    ) {
+        let view = self.view_matrix();
+        let clip = self.clip_rect;
+
+        for index in 0..self.sprites.len() {
+            if !self.is_selected(index, only) {
+                continue;
+            }
+            let matrix = view.multiply(&self.world_matrix(index));
+            // sprites 和 buffer 是不同字段，可以分别可变借用
+            self.sprites[index].render_with_matrix_clipped(&matrix, buffer, width, height, clip);
+        }
+    }
+
+    /// 按 z-order 依次以可变引用访问每个精灵和场景 buffer
+    ///
+    /// 安全的拆分借用接口，可在不使用 unsafe 的情况下实现自定义的逐精灵合成。
+    /// buffer 为 RGBA 格式，尺寸与场景一致；不会清屏，也不会应用相机变换。
+    pub fn for_each_sprite_with_buffer(
+        &mut self,
+        mut f: impl FnMut(&mut Box<dyn Sprite>, &mut [u8]),
+    ) {
+        self.needs_render = true;
+        self.sort_sprites();
+        let buffer = &mut self.buffer;
+        for sprite in self.sprites.iter_mut() {
+            f(sprite, buffer);
+        }
+    }
+
+    /// 立即模式绘制：用一次性变换将精灵合成到当前 buffer
+    ///
+    /// 精灵不会加入场景，适合调试绘制、提示框等非保留对象。
+    /// 应在 `render` 之后调用，否则下一次 `render` 会覆盖绘制结果。
+    /// 变换与场景内精灵一样位于世界坐标，会叠加相机变换。
+    pub fn draw_sprite_once(&mut self, sprite: &mut impl Sprite, transform: &Transform2D) {
+        self.needs_render = true;
+        let mut transform = *transform;
+        let w = sprite.width() as f32;
+        let h = sprite.height() as f32;
+        let matrix = self
+            .view_matrix()
+            .multiply(&transform.matrix_with_size(w, h));
+        sprite.render_with_matrix(&matrix, &mut self.buffer, self.width, self.height);
+    }
+
+    /// 将对象池中正在使用的精灵按 z-order 合成到当前 buffer
+    ///
+    /// 与 `draw_sprite_once` 一样应在 `render` 之后调用，会叠加相机变换
+    pub fn draw_pool(&mut self, pool: &mut SpritePool) {
+        self.needs_render = true;
+        let view = self.view_matrix();
+        let mut sprites: Vec<_> = pool.active_sprites_mut().collect();
+        sprites.sort_by_key(|s| (s.z_order(), s.layer_index()));
+
+        for sprite in sprites {
+            let matrix = view.multiply(&sprite.get_transform_matrix());
+            sprite.render_with_matrix(&matrix, &mut self.buffer, self.width, self.height);
+        }
+    }
+
+    /// 直接在 buffer 上绘制抗锯齿线段
+    ///
+    /// 按像素中心到线段的距离计算覆盖率，与已有像素做 Alpha 混合。
+    /// 应在 `render` 之后调用；坐标为世界坐标，会叠加相机变换。
+    ///
+    /// # Arguments
+    /// * `x0`, `y0` - 起点
+    /// * `x1`, `y1` - 终点
+    /// * `thickness` - 线宽（像素）
+    /// * `color` - 颜色 (0xRRGGBBAA)
+    pub fn draw_line(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, thickness: f32, color: u32) {
+        self.needs_render = true;
+        let view = self.view_matrix();
+        let start = view.transform_point(Vec2::new(x0, y0));
+        let end = view.transform_point(Vec2::new(x1, y1));
+        let half = (thickness * self.camera_zoom).max(0.0) / 2.0;
+
+        let (r, g, b, a) = unpack_rgba(color);
+        let rgba = [r, g, b, a];
+
+        // 只遍历线段包围盒（外扩半个线宽和 1 像素过渡带）
+        let pad = half + 1.0;
+        let min_x = (start.x.min(end.x) - pad).floor().max(0.0) as u32;
+        let min_y = (start.y.min(end.y) - pad).floor().max(0.0) as u32;
+        let max_x = ((start.x.max(end.x) + pad).ceil().max(0.0) as u32).min(self.width);
+        let max_y = ((start.y.max(end.y) + pad).ceil().max(0.0) as u32).min(self.height);
+
+        let segment = end - start;
+        let length_sq = segment.length_squared();
+
+        for py in min_y..max_y {
+            for px in min_x..max_x {
+                let center = Vec2::new(px as f32 + 0.5, py as f32 + 0.5);
+                let t = if length_sq > 0.0 {
+                    ((center - start).dot(&segment) / length_sq).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let distance = (center - (start + segment * t)).length();
+
+                // 距离边缘 1 像素内线性过渡
+                let coverage = (half + 0.5 - distance).clamp(0.0, 1.0);
+                if coverage <= 0.0 {
+                    continue;
+                }
+
+                let alpha = (rgba[3] as f32 * coverage).round() as u8;
+                let idx = ((py * self.width + px) * 4) as usize;
+                blend_pixel(
+                    &mut self.buffer[idx..idx + 4],
+                    [rgba[0], rgba[1], rgba[2], alpha],
+                    BlendMode::Normal,
+                );
+            }
+        }
+    }
+
+    /// 标记场景已改动，下次 `render` 会重新渲染
+    ///
+    /// 程序化背景函数随时间变化等场景无法感知的改动，需要手动调用
+    pub fn mark_dirty(&mut self) {
+        self.needs_render = true;
+    }
+
+    /// 上次渲染后场景是否有改动
+    pub fn needs_render(&self) -> bool {
+        self.needs_render
+    }
+
+    /// 标记需要重新排序（当精灵 z-order 改变时调用）
+    pub fn mark_needs_sort(&mut self) {
+        self.needs_render = true;
+        self.needs_sort = true;
+    }
+}
+
+/// 为 Scene 实现 Debug trait
+impl std::fmt::Debug for Scene {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scene")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("sprite_count", &self.sprites.len())
+            .field("background_color", &self.background_color)
+            .field("camera_position", &self.camera_position)
+            .field("camera_zoom", &self.camera_zoom)
+            .field("accumulation", &self.accumulation)
+            .field("supersample", &self.supersample)
+            .field("clear_enabled", &self.clear_enabled)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::sprite::ImageSprite;
+
+    #[test]
+    fn test_create_scene() {
+        let scene = Scene::new(800, 600);
+        assert_eq!(scene.width(), 800);
+        assert_eq!(scene.height(), 600);
+        assert_eq!(scene.buffer().len(), 800 * 600 * 4);
+    }
+
+    #[test]
+    fn test_add_remove_sprite() {
+        let mut scene = Scene::new(100, 100);
+        let sprite = ImageSprite::new(50, 50, ImageFormat::Rgba);
+        let id = scene.add(sprite);
+
+        assert_eq!(scene.sprite_count(), 1);
+
+        let removed = scene.remove(id);
+        assert!(removed);
+        assert_eq!(scene.sprite_count(), 0);
+    }
+
+    #[test]
+    fn test_z_order_sorting() {
+        let mut scene = Scene::new(100, 100);
+
+        let mut sprite1 = ImageSprite::new(10, 10, ImageFormat::Rgba);
+        sprite1.set_z_order(10);
+
+        let mut sprite2 = ImageSprite::new(10, 10, ImageFormat::Rgba);
+        sprite2.set_z_order(5);
+
+        let mut sprite3 = ImageSprite::new(10, 10, ImageFormat::Rgba);
+        sprite3.set_z_order(15);
+
+        scene.add(sprite1);
+        scene.add(sprite2);
+        scene.add(sprite3);
+
+        // 渲染触发排序
+        scene.render();
+
+        // 验证排序结果：z_order 5, 10, 15
+        let z_orders: Vec<i32> = scene.sprites.iter().map(|s| s.z_order()).collect();
+        assert_eq!(z_orders, vec![5, 10, 15]);
+    }
+
+    #[test]
+    fn test_layer_index_breaks_ties() {
+        let mut scene = Scene::new(10, 10);
+
+        let mut ids = Vec::new();
+        for layer in [2, 0, 1] {
+            let mut sprite = ImageSprite::new(10, 10, ImageFormat::Rgba);
+            sprite.set_z_order(3);
+            sprite.set_layer_index(layer);
+            ids.push(scene.add(sprite));
+        }
+        scene.render();
+
+        // z-order 相同，按层内索引 0, 1, 2 渲染
+        let order: Vec<u64> = scene.sprites.iter().map(|s| s.id()).collect();
+        assert_eq!(order, vec![ids[1], ids[2], ids[0]]);
+    }
+
+    #[test]
+    fn test_nan_position_still_renders() {
+        let mut scene = Scene::new(10, 10);
+        let mut sprite = ImageSprite::create_rectangle(4, 4, 255, 0, 0, 255);
+        sprite.set_anchor(0.0, 0.0).set_position(2.0, 2.0);
+        sprite.set_position(f32::NAN, f32::NAN);
+        scene.add(sprite);
+
+        scene.render();
+
+        // NaN 被拒绝，精灵仍在原位置
+        let idx = ((3 * 10 + 3) * 4) as usize;
+        assert_eq!(scene.buffer()[idx], 255);
+    }
+
+    #[test]
+    fn test_content_bounds_encloses_all_sprites() {
+        let mut scene = Scene::new(10, 10);
+        assert!(scene.content_bounds().is_none());
+
+        let mut a = ImageSprite::create_rectangle(4, 4, 255, 0, 0, 255);
+        a.set_anchor(0.0, 0.0).set_position(-20.0, 5.0);
+        let mut b = ImageSprite::create_rectangle(2, 6, 0, 255, 0, 255);
+        b.set_anchor(0.0, 0.0).set_position(30.0, -10.0);
+        scene.add(a);
+        scene.add(b);
+
+        let (min, max) = scene.content_bounds().unwrap();
+        assert!((min.x - -20.0).abs() < 1e-4);
+        assert!((min.y - -10.0).abs() < 1e-4);
+        assert!((max.x - 32.0).abs() < 1e-4);
+        assert!((max.y - 9.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_draw_pool_renders_active_only() {
+        let mut scene = Scene::new(10, 1);
+        let mut pool = SpritePool::new(2, 5, 1, ImageFormat::Rgba);
+
+        let released = {
+            let sprite = pool.acquire().unwrap();
+            sprite.buffer_mut().fill(255);
+            sprite.set_anchor(0.0, 0.0);
+            sprite.id()
+        };
+        let sprite = pool.acquire().unwrap();
+        sprite.buffer_mut().fill(255);
+        sprite.set_anchor(0.0, 0.0).set_position(5.0, 0.0);
+        pool.release(released);
+
+        scene.render();
+        scene.draw_pool(&mut pool);
+
+        assert_eq!(scene.buffer()[0], 0);
+        assert_eq!(scene.buffer()[7 * 4], 255);
+    }
+
+    #[test]
+    fn test_supersample_smooths_edges_and_reuses_buffer() {
+        let mut scene = Scene::new(4, 4);
+        scene.set_background_color(0, 0, 0, 255).set_supersample(2);
+
+        // 半像素偏移的精灵：边缘像素只被覆盖一半
+        let mut sprite = ImageSprite::create_rectangle(2, 4, 255, 255, 255, 255);
+        sprite.set_anchor(0.0, 0.0).set_position(0.5, 0.0);
+        scene.add(sprite);
+
+        scene.render();
+        let ptr = scene.supersample_buffer.as_ptr();
+        let capacity = scene.supersample_buffer.capacity();
+        assert_eq!(scene.buffer()[0], 128);
+        assert_eq!(scene.buffer()[4], 255);
+        assert_eq!(scene.buffer()[2 * 4], 128);
+        assert_eq!(scene.buffer()[3 * 4], 0);
+
+        // 第二次渲染复用同一块缓冲
+        scene.render();
+        assert_eq!(scene.supersample_buffer.as_ptr(), ptr);
+        assert_eq!(scene.supersample_buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_for_each_sprite_with_buffer_matches_render() {
+        let build = || {
+            let mut scene = Scene::new(8, 8);
+            let mut back = ImageSprite::create_rectangle(6, 6, 255, 0, 0, 255);
+            back.set_anchor(0.0, 0.0).set_position(1.0, 1.0);
+            back.set_z_order(1);
+            let mut front = ImageSprite::create_rectangle(3, 3, 0, 0, 255, 128);
+            front.set_anchor(0.0, 0.0).set_position(2.0, 2.0);
+            front.set_z_order(2);
+            scene.add(front);
+            scene.add(back);
+            scene
+        };
+
+        let mut expected = build();
+        expected.render();
+
+        let mut scene = build();
+        scene.render_only(&[]);
+        scene.for_each_sprite_with_buffer(|sprite, buffer| sprite.render_to(buffer, 8, 8));
+
+        assert_eq!(scene.buffer(), expected.buffer());
+    }
+
+    #[test]
+    fn test_render_skipped_when_clean() {
+        let mut scene = Scene::new(4, 4);
+        let mut sprite = ImageSprite::create_rectangle(2, 2, 255, 0, 0, 255);
+        sprite.set_anchor(0.0, 0.0);
+        let id = scene.add(sprite);
+
+        scene.render();
+        assert!(!scene.needs_render());
+
+        // 没有改动时不重新合成：手动写入的像素被保留
+        scene.buffer[0] = 7;
+        scene.render();
+        assert_eq!(scene.buffer()[0], 7);
+
+        // 修改精灵后重新渲染
+        scene
+            .get_sprite_mut(id)
+            .unwrap()
+            .transform_mut()
+            .set_position(1.0, 0.0);
+        assert!(scene.needs_render());
+        scene.render();
+        assert_eq!(scene.buffer()[0], 0);
+        assert_eq!(scene.buffer()[4], 255);
+
+        // 只读查询不标记改动
+        scene.world_bounds(id);
+        assert!(!scene.needs_render());
+        scene.set_background_color(1, 2, 3, 255);
+        assert!(scene.needs_render());
+    }
+
+    #[test]
+    fn test_to_png_roundtrip() {
+        let mut scene = Scene::new(3, 2);
+        scene.set_background_color(10, 20, 30, 255);
+        let mut sprite = ImageSprite::create_rectangle(1, 1, 255, 0, 0, 128);
+        sprite.set_anchor(0.0, 0.0).set_position(2.0, 1.0);
+        scene.add(sprite);
+        scene.render();
+
+        let bytes = scene.to_png();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(info.color_type, png::ColorType::Rgba);
+        assert_eq!(&pixels[..info.buffer_size()], scene.buffer());
+
+        assert!(Scene::new(0, 0).to_png().is_empty());
+    }
+
+    #[test]
+    fn test_sprite_screen_area() {
+        let mut scene = Scene::new(100, 100);
+        let mut sprite = ImageSprite::create_rectangle(10, 10, 255, 255, 255, 255);
+        sprite
+            .set_anchor(0.0, 0.0)
+            .set_position(10.0, 10.0)
+            .set_uniform_scale(2.0);
+        let id = scene.add(sprite);
+
+        assert_eq!(scene.sprite_screen_area(id), 400);
+
+        // 一半移出屏幕
+        scene.set_camera_position(20.0, 0.0);
+        assert_eq!(scene.sprite_screen_area(id), 200);
+        assert_eq!(scene.sprite_screen_area(999_999), 0);
+    }
+
+    #[test]
+    fn test_render_only_subset() {
+        let mut scene = Scene::new(30, 10);
+
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            let mut sprite = ImageSprite::create_rectangle(10, 10, 255, 255, 255, 255);
+            sprite
+                .set_anchor(0.0, 0.0)
+                .set_position(i as f32 * 10.0, 0.0);
+            ids.push(scene.add(sprite));
+        }
+
+        scene.render_only(&[ids[1]]);
+
+        let pixel = |x: u32| scene.buffer()[(x * 4) as usize];
+        assert_eq!(pixel(5), 0);
+        assert_eq!(pixel(15), 255);
+        assert_eq!(pixel(25), 0);
+    }
+
+    #[test]
+    fn test_background_color() {
+        let mut scene = Scene::new(2, 2);
+        scene.set_background_color(255, 0, 0, 255);
+        scene.render();
 
         // 验证背景色
         assert_eq!(scene.buffer()[0], 255); // R
@@ -252,4 +1661,713 @@ mod tests {
         assert_eq!(scene.buffer()[2], 0); // B
         assert_eq!(scene.buffer()[3], 255); // A
     }
+
+    #[test]
+    fn test_accumulation_exceeds_clamp() {
+        let mut scene = Scene::new(4, 4);
+        scene.enable_accumulation(ToneMap::Clamp);
+
+        // 5 个完全重叠的叠加精灵，每个贡献 100
+        for _ in 0..5 {
+            let mut sprite = ImageSprite::create_rectangle(4, 4, 100, 100, 100, 255);
+            sprite
+                .set_anchor(0.0, 0.0)
+                .set_blend_mode(BlendMode::Additive);
+            scene.add(sprite);
+        }
+        scene.render();
+
+        // 色调映射前的累积值超过单精灵截断上限
+        let accum = scene.accumulation_buffer().unwrap();
+        assert!((accum[0] - 500.0).abs() < 1e-3);
+        assert!(accum[0] > 255.0);
+
+        // 截断映射后输出为 255
+        assert_eq!(scene.buffer()[0], 255);
+    }
+
+    #[test]
+    fn test_accumulation_matches_multiply_and_screen() {
+        for mode in [BlendMode::Multiply, BlendMode::Screen] {
+            let mut scene = Scene::new(2, 2);
+            scene.set_background_color(200, 100, 50, 255);
+            let mut sprite = ImageSprite::create_rectangle(2, 2, 255, 128, 64, 255);
+            sprite.set_anchor(0.0, 0.0).set_blend_mode(mode);
+            scene.add(sprite);
+
+            scene.render();
+            let normal = scene.buffer()[..4].to_vec();
+            scene.enable_accumulation(ToneMap::Clamp);
+            scene.render();
+            let accumulated = &scene.buffer()[..4];
+
+            for c in 0..4 {
+                assert!(normal[c].abs_diff(accumulated[c]) <= 1, "{mode:?}");
+            }
+        }
+
+        // 白色正片叠底不改变背景
+        let mut scene = Scene::new(2, 2);
+        scene.set_background_color(200, 100, 50, 255);
+        scene.enable_accumulation(ToneMap::Clamp);
+        let mut sprite = ImageSprite::create_rectangle(2, 2, 255, 255, 255, 255);
+        sprite
+            .set_anchor(0.0, 0.0)
+            .set_blend_mode(BlendMode::Multiply);
+        scene.add(sprite);
+        scene.render();
+        assert_eq!(&scene.buffer()[..4], &[200, 100, 50, 255]);
+    }
+
+    #[test]
+    fn test_accumulation_reinhard_tonemap() {
+        let mut scene = Scene::new(2, 2);
+        scene.enable_accumulation(ToneMap::Reinhard);
+
+        for _ in 0..3 {
+            let mut sprite = ImageSprite::create_rectangle(2, 2, 255, 255, 255, 255);
+            sprite
+                .set_anchor(0.0, 0.0)
+                .set_blend_mode(BlendMode::Additive);
+            scene.add(sprite);
+        }
+        scene.render();
+
+        // 3 倍亮度经 Reinhard 压缩后仍未饱和
+        let value = scene.buffer()[0];
+        assert!(value > 127 && value < 255);
+    }
+
+    #[test]
+    fn test_sprite_visible_on_screen() {
+        let mut scene = Scene::new(100, 100);
+        let mut sprite = ImageSprite::create_rectangle(10, 10, 255, 255, 255, 255);
+        sprite.set_position(50.0, 50.0);
+        let id = scene.add(sprite);
+
+        assert!(scene.is_sprite_visible_on_screen(id));
+
+        // 移出屏幕右侧
+        scene
+            .get_sprite_mut(id)
+            .unwrap()
+            .transform_mut()
+            .set_position(200.0, 50.0);
+        assert!(!scene.is_sprite_visible_on_screen(id));
+
+        // 不存在的精灵视为不可见
+        assert!(!scene.is_sprite_visible_on_screen(u64::MAX));
+    }
+
+    #[test]
+    fn test_world_bounds() {
+        let mut scene = Scene::new(100, 100);
+        let mut sprite = ImageSprite::create_rectangle(10, 20, 255, 255, 255, 255);
+        sprite.set_position(50.0, 50.0);
+        let id = scene.add(sprite);
+
+        let (min, max) = scene.world_bounds(id).unwrap();
+        assert!((min.x - 45.0).abs() < 1e-5);
+        assert!((min.y - 40.0).abs() < 1e-5);
+        assert!((max.x - 55.0).abs() < 1e-5);
+        assert!((max.y - 60.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_background_color_readback() {
+        let mut scene = Scene::new(2, 2);
+        assert_eq!(scene.background_color(), 0x000000FF);
+
+        scene.set_background_color(0x12, 0x34, 0x56, 0x78);
+        assert_eq!(scene.background_color(), 0x12345678);
+
+        scene.set_background_color_hex(0xAABBCCDD);
+        assert_eq!(scene.background_color(), 0xAABBCCDD);
+    }
+
+    #[test]
+    fn test_background_image_bilinear() {
+        let mut scene = Scene::new(8, 8);
+        // 2x2 灰度渐变：左列黑，右列白
+        scene
+            .set_background_image(
+                vec![0, 255, 0, 255],
+                2,
+                2,
+                ImageFormat::Grayscale,
+                SamplingMode::Bilinear,
+            )
+            .unwrap();
+        scene.render();
+
+        let row: Vec<u8> = (0..8).map(|x| scene.buffer()[x * 4]).collect();
+        // 两个源像素之间出现插值的中间值，且从左到右单调递增
+        assert!(row.iter().any(|&v| v > 0 && v < 255));
+        assert!(row.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(row[0], 0);
+        assert_eq!(row[7], 255);
+    }
+
+    #[test]
+    fn test_background_image_nearest() {
+        let mut scene = Scene::new(8, 8);
+        scene
+            .set_background_image(
+                vec![0, 255, 0, 255],
+                2,
+                2,
+                ImageFormat::Grayscale,
+                SamplingMode::Nearest,
+            )
+            .unwrap();
+        scene.render();
+
+        let row: Vec<u8> = (0..8).map(|x| scene.buffer()[x * 4]).collect();
+        assert_eq!(row, vec![0, 0, 0, 0, 255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_background_image_rejects_wrong_length() {
+        let mut scene = Scene::new(4, 4);
+        let result = scene.set_background_image(
+            vec![0, 255, 0],
+            2,
+            2,
+            ImageFormat::Grayscale,
+            SamplingMode::Nearest,
+        );
+        assert!(result.is_err());
+        assert!(scene.background_image.is_none());
+        // 不会因越界采样而 panic
+        scene.render();
+    }
+
+    #[test]
+    fn test_global_opacity_halves_alpha() {
+        let mut scene = Scene::new(4, 4);
+        let mut sprite = ImageSprite::create_rectangle(2, 2, 255, 0, 0, 200);
+        sprite.set_anchor(0.0, 0.0);
+        scene.add(sprite);
+
+        scene.render();
+        let full: Vec<u8> = scene.buffer().chunks(4).map(|p| p[3]).collect();
+
+        scene.set_global_opacity(0.5);
+        scene.render();
+        let half: Vec<u8> = scene.buffer().chunks(4).map(|p| p[3]).collect();
+
+        for (f, h) in full.iter().zip(half.iter()) {
+            assert_eq!(*h, (*f as f32 * 0.5).round() as u8);
+        }
+
+        // 超出范围被钳制
+        scene.set_global_opacity(2.0);
+        assert_eq!(scene.global_opacity(), 1.0);
+
+        // 非有限值被忽略
+        scene.set_global_opacity(0.25);
+        scene.set_global_opacity(f32::NAN);
+        assert_eq!(scene.global_opacity(), 0.25);
+        scene.set_global_opacity(f32::INFINITY);
+        assert_eq!(scene.global_opacity(), 0.25);
+    }
+
+    #[test]
+    fn test_background_fn_checkerboard() {
+        let mut scene = Scene::new(4, 4);
+        scene.set_background_fn(|x, y| {
+            if (x + y) % 2 == 0 {
+                0xFFFFFFFF
+            } else {
+                0x000000FF
+            }
+        });
+        scene.render();
+
+        for y in 0..4u32 {
+            for x in 0..4u32 {
+                let idx = ((y * 4 + x) * 4) as usize;
+                let expected = if (x + y) % 2 == 0 { 255 } else { 0 };
+                assert_eq!(scene.buffer()[idx], expected);
+                assert_eq!(scene.buffer()[idx + 3], 255);
+            }
+        }
+
+        // 移除后回到背景颜色
+        scene.clear_background_fn();
+        scene.render();
+        assert_eq!(scene.buffer()[0], 0);
+    }
+
+    #[test]
+    fn test_buffer_hash_tracks_changes() {
+        let mut scene = Scene::new(20, 20);
+        let sprite = ImageSprite::create_rectangle(4, 4, 255, 255, 255, 255);
+        let id = scene.add(sprite);
+
+        scene.render();
+        let first = scene.buffer_hash();
+        scene.render();
+        assert_eq!(scene.buffer_hash(), first);
+
+        scene
+            .get_sprite_mut(id)
+            .unwrap()
+            .transform_mut()
+            .translate(5.0, 5.0);
+        scene.render();
+        assert_ne!(scene.buffer_hash(), first);
+    }
+
+    #[test]
+    fn test_draw_sprite_once() {
+        let mut scene = Scene::new(10, 10);
+        scene.render();
+
+        let mut sprite = ImageSprite::create_rectangle(2, 2, 0, 255, 0, 255);
+        let mut transform = Transform2D::new();
+        transform.set_anchor(0.0, 0.0).set_position(4.0, 4.0);
+        scene.draw_sprite_once(&mut sprite, &transform);
+
+        let idx = ((4 * 10 + 4) * 4) as usize;
+        assert_eq!(&scene.buffer()[idx..idx + 4], &[0, 255, 0, 255]);
+        assert_eq!(&scene.buffer()[0..4], &[0, 0, 0, 255]);
+        assert_eq!(scene.sprite_count(), 0);
+    }
+
+    #[test]
+    fn test_draw_line_antialiased() {
+        let mut scene = Scene::new(10, 10);
+        scene.render();
+        scene.draw_line(1.0, 1.0, 8.0, 8.0, 1.0, 0xFFFFFFFF);
+
+        let pixel = |x: u32, y: u32| scene.buffer()[((y * 10 + x) * 4) as usize];
+
+        // 端点与内部点被完全覆盖
+        assert_eq!(pixel(1, 1), 255);
+        assert_eq!(pixel(4, 4), 255);
+        assert_eq!(pixel(7, 7), 255);
+
+        // 线段两侧有部分覆盖的过渡像素
+        let edge = pixel(5, 4);
+        assert!(edge > 0 && edge < 255);
+
+        // 远离线段的像素保持背景色
+        assert_eq!(pixel(8, 1), 0);
+    }
+
+    #[test]
+    fn test_zoom_clamped_to_view_bounds() {
+        let mut scene = Scene::new(100, 100);
+        scene.set_view_bounds(0.5, 2.0, (0.0, 0.0, 100.0, 100.0));
+
+        scene.zoom(3.0);
+        assert!((scene.camera_zoom() - 2.0).abs() < 1e-6);
+
+        scene.set_camera_zoom(0.1);
+        assert!((scene.camera_zoom() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pan_clamped_to_limit_rect() {
+        let mut scene = Scene::new(100, 100);
+        scene.set_view_bounds(1.0, 1.0, (-10.0, -10.0, 20.0, 20.0));
+
+        scene.pan(500.0, -500.0);
+        let position = scene.camera_position();
+        assert!((position.x - 10.0).abs() < 1e-6);
+        assert!((position.y + 10.0).abs() < 1e-6);
+
+        // 非有限值被忽略，原有约束保持生效
+        scene.set_view_bounds(f32::NAN, f32::NAN, (0.0, 0.0, 1.0, 1.0));
+        scene.set_view_bounds(1.0, 1.0, (f32::NAN, 0.0, f32::INFINITY, 1.0));
+        scene.pan(-500.0, 500.0);
+        let position = scene.camera_position();
+        assert!((position.x + 10.0).abs() < 1e-6);
+        assert!((position.y - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_camera_offsets_rendering() {
+        let mut scene = Scene::new(10, 10);
+        let mut sprite = ImageSprite::create_rectangle(2, 2, 255, 255, 255, 255);
+        sprite.set_anchor(0.0, 0.0).set_position(5.0, 5.0);
+        scene.add(sprite);
+
+        // 相机右移 5 个单位后，精灵出现在屏幕左上角
+        scene.set_camera_position(5.0, 5.0);
+        scene.render();
+        assert_eq!(scene.buffer()[0], 255);
+
+        let idx = ((5 * 10 + 5) * 4) as usize;
+        assert_eq!(scene.buffer()[idx], 0);
+    }
+
+    #[test]
+    fn test_clear_disabled_keeps_existing_pixels() {
+        let mut scene = Scene::new(4, 4);
+        scene.buffer.fill(77);
+        scene.set_clear_enabled(false);
+        assert!(!scene.clear_enabled());
+
+        let mut sprite = ImageSprite::create_rectangle(1, 1, 255, 0, 0, 255);
+        sprite.set_anchor(0.0, 0.0);
+        scene.add(sprite);
+        scene.render();
+
+        assert_eq!(&scene.buffer()[..4], &[255, 0, 0, 255]);
+        // 精灵之外的像素保持预填充的颜色
+        assert!(scene.buffer()[4..].iter().all(|&v| v == 77));
+    }
+
+    #[test]
+    fn test_pick_topmost_sprite() {
+        let mut scene = Scene::new(20, 20);
+        let mut bottom = ImageSprite::create_rectangle(10, 10, 255, 0, 0, 255);
+        bottom.set_anchor(0.0, 0.0).set_z_order(1);
+        let bottom = scene.add(bottom);
+
+        // 后添加但 z-order 更低，被压在下面
+        let mut top = ImageSprite::create_rectangle(4, 4, 0, 255, 0, 255);
+        top.set_anchor(0.0, 0.0).set_position(2.0, 2.0);
+        top.set_z_order(2);
+        let top = scene.add(top);
+
+        assert_eq!(scene.pick(3.0, 3.0), Some(top));
+        assert_eq!(scene.pick(8.0, 8.0), Some(bottom));
+        assert_eq!(scene.pick(15.0, 15.0), None);
+
+        // 透明像素不命中
+        if let Some(sprite) = scene
+            .get_sprite_mut(top)
+            .and_then(|s| s.as_image_sprite_mut())
+        {
+            sprite.buffer_mut()[(4 + 1) * 4 + 3] = 0; // 像素 (1, 1)
+        }
+        assert_eq!(scene.pick(3.5, 3.5), Some(bottom));
+
+        // 拾取坐标经过相机变换
+        scene.set_camera_position(5.0, 5.0);
+        assert_eq!(scene.pick(0.0, 0.0), Some(top));
+        assert_eq!(scene.pick(2.0, 2.0), Some(bottom));
+        assert_eq!(scene.pick(6.0, 6.0), None);
+    }
+
+    #[test]
+    fn test_pick_alpha_threshold_and_opacity() {
+        let mut scene = Scene::new(10, 10);
+        let mut bottom = ImageSprite::create_rectangle(10, 10, 255, 0, 0, 255);
+        bottom.set_anchor(0.0, 0.0);
+        let bottom = scene.add(bottom);
+        let mut glow = ImageSprite::create_rectangle(10, 10, 255, 255, 255, 100);
+        glow.set_anchor(0.0, 0.0).set_z_order(1);
+        let glow = scene.add(glow);
+
+        assert_eq!(scene.pick(5.0, 5.0), Some(glow));
+        // alpha 100 低于阈值，落到下层
+        scene.set_pick_alpha_threshold(128);
+        assert_eq!(scene.pick(5.0, 5.0), Some(bottom));
+        scene.set_pick_alpha_threshold(99);
+        assert_eq!(scene.pick(5.0, 5.0), Some(glow));
+
+        // 不透明度参与有效 alpha：100 * 0.5 = 50
+        scene.get_sprite_mut(glow).unwrap().set_opacity(0.5);
+        assert_eq!(scene.pick(5.0, 5.0), Some(bottom));
+        scene.set_pick_alpha_threshold(0);
+        assert_eq!(scene.pick(5.0, 5.0), Some(glow));
+        scene.get_sprite_mut(glow).unwrap().set_opacity(0.0);
+        assert_eq!(scene.pick(5.0, 5.0), Some(bottom));
+    }
+
+    #[test]
+    fn test_screen_corners_follow_parent_and_camera() {
+        let mut scene = Scene::new(20, 20);
+        let mut parent = ImageSprite::create_rectangle(2, 2, 255, 0, 0, 255);
+        parent.set_anchor(0.0, 0.0).set_position(4.0, 4.0);
+        let parent = scene.add(parent);
+        let mut child = ImageSprite::create_rectangle(4, 2, 0, 255, 0, 255);
+        child.set_anchor(0.0, 0.0).set_position(1.0, 1.0);
+        let child = scene.add(child);
+        scene.set_parent(child, Some(parent));
+        scene.set_camera_position(2.0, 0.0);
+        scene.render();
+
+        let corners = scene.screen_corners(child).unwrap();
+        assert_eq!(corners[0], Vec2::new(3.0, 5.0));
+        assert_eq!(corners[2], Vec2::new(7.0, 7.0));
+        assert!(!scene.needs_render());
+        assert!(scene.screen_corners(u64::MAX).is_none());
+    }
+
+    #[test]
+    fn test_parent_moves_child() {
+        let mut scene = Scene::new(20, 20);
+        let mut parent = ImageSprite::create_rectangle(2, 2, 255, 0, 0, 255);
+        parent.set_anchor(0.0, 0.0).set_position(2.0, 2.0);
+        let parent = scene.add(parent);
+
+        let mut child = ImageSprite::create_rectangle(2, 2, 0, 255, 0, 255);
+        child.set_anchor(0.0, 0.0).set_position(5.0, 0.0);
+        let child = scene.add(child);
+
+        assert!(scene.set_parent(child, Some(parent)));
+        assert_eq!(scene.parent(child), Some(parent));
+
+        // 子精灵位置相对于父精灵
+        let (min, _) = scene.world_bounds(child).unwrap();
+        assert!((min - Vec2::new(7.0, 2.0)).is_zero(1e-5));
+
+        if let Some(sprite) = scene.get_sprite_mut(parent) {
+            sprite.transform_mut().set_position(10.0, 10.0);
+        }
+        scene.render();
+        let idx = ((10 * 20 + 15) * 4) as usize;
+        assert_eq!(&scene.buffer()[idx..idx + 4], &[0, 255, 0, 255]);
+
+        // 删除父精灵后子精灵回到根级
+        scene.remove(parent);
+        assert_eq!(scene.parent(child), None);
+        let (min, _) = scene.world_bounds(child).unwrap();
+        assert!((min - Vec2::new(5.0, 0.0)).is_zero(1e-5));
+    }
+
+    #[test]
+    fn test_set_parent_rejects_cycles() {
+        let mut scene = Scene::new(4, 4);
+        let a = scene.add(ImageSprite::create_rectangle(1, 1, 0, 0, 0, 255));
+        let b = scene.add(ImageSprite::create_rectangle(1, 1, 0, 0, 0, 255));
+        let c = scene.add(ImageSprite::create_rectangle(1, 1, 0, 0, 0, 255));
+
+        assert!(scene.set_parent(b, Some(a)));
+        assert!(scene.set_parent(c, Some(b)));
+        assert!(!scene.set_parent(a, Some(c)));
+        assert!(!scene.set_parent(a, Some(a)));
+        assert!(!scene.set_parent(a, Some(999_999)));
+        assert_eq!(scene.parent(a), None);
+
+        assert!(scene.set_parent(c, None));
+        assert!(scene.set_parent(a, Some(c)));
+    }
+
+    #[test]
+    fn test_child_renders_after_parent() {
+        let mut scene = Scene::new(4, 4);
+        let mut parent = ImageSprite::create_rectangle(4, 4, 255, 0, 0, 255);
+        parent.set_anchor(0.0, 0.0).set_z_order(5);
+        let parent = scene.add(parent);
+
+        // 子精灵 z-order 更低，但仍绘制在父精灵之上
+        let mut child = ImageSprite::create_rectangle(4, 4, 0, 0, 255, 255);
+        child.set_anchor(0.0, 0.0).set_z_order(-5);
+        let child = scene.add(child);
+        scene.set_parent(child, Some(parent));
+
+        scene.render();
+        assert_eq!(&scene.buffer()[..4], &[0, 0, 255, 255]);
+        assert_eq!(scene.pick(1.0, 1.0), Some(child));
+    }
+
+    #[test]
+    fn test_query_sprites_by_id() {
+        let mut scene = Scene::new(4, 4);
+        let mut a = ImageSprite::create_rectangle(1, 1, 0, 0, 0, 255);
+        a.set_z_order(2);
+        let mut b = ImageSprite::create_rectangle(2, 1, 0, 0, 0, 255);
+        b.set_z_order(0);
+        let mut c = ImageSprite::create_rectangle(3, 1, 0, 0, 0, 255);
+        c.set_z_order(1);
+        let (a, b, c) = (scene.add(a), scene.add(b), scene.add(c));
+        scene.render();
+
+        // 按渲染顺序列出，未渲染时也一样
+        assert_eq!(scene.sprite_ids(), vec![b, c, a]);
+        if let Some(sprite) = scene.get_sprite_mut(a) {
+            sprite.set_z_order(-1);
+        }
+        scene.mark_needs_sort();
+        assert_eq!(scene.sprite_ids(), vec![a, b, c]);
+
+        assert!(scene.contains(b));
+        assert!(!scene.contains(999_999));
+        assert_eq!(scene.get_sprite(c).map(|s| s.width()), Some(3));
+        assert!(scene.get_sprite(999_999).is_none());
+
+        // 只读访问不标记改动
+        scene.render();
+        let _ = scene.get_sprite(a);
+        assert!(!scene.needs_render());
+
+        scene.remove(b);
+        assert!(!scene.contains(b));
+        assert_eq!(scene.sprite_ids(), vec![a, c]);
+    }
+
+    #[test]
+    fn test_named_sprites() {
+        let mut scene = Scene::new(4, 4);
+        let player = scene.add_named(ImageSprite::create_rectangle(2, 2, 0, 0, 0, 255), "player");
+        scene.add(ImageSprite::create_rectangle(1, 1, 0, 0, 0, 255));
+
+        assert_eq!(
+            scene.get_sprite_by_name("player").map(|s| s.id()),
+            Some(player)
+        );
+        assert!(scene.get_sprite_by_name("enemy").is_none());
+
+        // 重名时覆盖，原精灵仍在场景中
+        let replacement =
+            scene.add_named(ImageSprite::create_rectangle(3, 3, 0, 0, 0, 255), "player");
+        assert_eq!(
+            scene.get_sprite_by_name("player").map(|s| s.id()),
+            Some(replacement)
+        );
+        assert!(scene.contains(player));
+
+        scene.remove(replacement);
+        assert!(scene.get_sprite_by_name("player").is_none());
+        assert_eq!(scene.sprite_count(), 2);
+    }
+
+    #[test]
+    fn test_render_if_dirty() {
+        let mut scene = Scene::new(4, 4);
+        assert!(scene.render_if_dirty());
+        assert!(!scene.render_if_dirty());
+
+        let id = scene.add(ImageSprite::create_rectangle(1, 1, 255, 0, 0, 255));
+        assert!(scene.render_if_dirty());
+        assert!(!scene.render_if_dirty());
+
+        // 通过可变引用修改变换也会标记改动
+        if let Some(sprite) = scene.get_sprite_mut(id) {
+            sprite.transform_mut().set_position(2.0, 2.0);
+        }
+        assert!(scene.render_if_dirty());
+
+        scene.set_background_color(0, 0, 255, 255);
+        assert!(scene.render_if_dirty());
+        scene.remove(id);
+        assert!(scene.render_if_dirty());
+        assert!(!scene.render_if_dirty());
+    }
+
+    #[test]
+    fn test_clip_rect_limits_rendering() {
+        let mut scene = Scene::new(10, 10);
+        scene.render();
+        scene.set_background_color(0, 0, 255, 255);
+        scene.set_clip_rect(Some((0, 0, 5, 10)));
+        // 精灵横跨裁剪边界 x = 5
+        let mut sprite = ImageSprite::create_rectangle(6, 2, 255, 0, 0, 255);
+        sprite.set_anchor(0.0, 0.0).set_position(2.0, 4.0);
+        scene.add(sprite);
+        scene.render();
+
+        fn pixel(scene: &Scene, x: usize, y: usize) -> &[u8] {
+            &scene.buffer()[(y * 10 + x) * 4..(y * 10 + x) * 4 + 4]
+        }
+        assert_eq!(pixel(&scene, 4, 4), [255, 0, 0, 255]);
+        assert_eq!(pixel(&scene, 0, 0), [0, 0, 255, 255]);
+        // 裁剪区域外保持上一帧的黑色背景，精灵也不会画出去
+        assert_eq!(pixel(&scene, 5, 4), [0, 0, 0, 255]);
+        assert_eq!(pixel(&scene, 7, 5), [0, 0, 0, 255]);
+        assert_eq!(pixel(&scene, 9, 9), [0, 0, 0, 255]);
+
+        scene.set_clip_rect(None);
+        scene.render();
+        assert_eq!(pixel(&scene, 7, 5), [255, 0, 0, 255]);
+        assert_eq!(pixel(&scene, 9, 9), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_sprite_lookup_stress() {
+        let mut scene = Scene::new(4, 4);
+        let ids: Vec<u64> = (0..10_000)
+            .map(|i| {
+                let mut sprite = ImageSprite::create_rectangle(1, 1, 255, 255, 255, 255);
+                // 倒序 z-order，排序后列表顺序与添加顺序相反
+                sprite.set_z_order(-i);
+                scene.add(sprite)
+            })
+            .collect();
+        scene.render();
+
+        for &id in &ids {
+            assert_eq!(scene.get_sprite(id).map(|s| s.id()), Some(id));
+        }
+
+        // 删除部分精灵后剩余精灵仍能正确查找
+        for &id in ids.iter().step_by(100) {
+            assert!(scene.remove(id));
+        }
+        assert_eq!(scene.sprite_count(), 9_900);
+        for (i, &id) in ids.iter().enumerate() {
+            assert_eq!(scene.contains(id), i % 100 != 0);
+            if i % 100 != 0 {
+                assert_eq!(scene.get_sprite_mut(id).map(|s| s.id()), Some(id));
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_into_external_buffer() {
+        let mut scene = Scene::new(4, 4);
+        scene.set_background_color(0, 0, 255, 255);
+        let mut sprite = ImageSprite::create_rectangle(2, 2, 255, 0, 0, 255);
+        sprite.set_anchor(0.0, 0.0);
+        scene.add(sprite);
+
+        // 多余部分保持不变
+        let mut target = vec![7u8; 4 * 4 * 4 + 4];
+        assert!(scene.render_into(&mut target, 4, 4).is_ok());
+        assert_eq!(&target[64..], &[7, 7, 7, 7]);
+        // 场景自身 buffer 未被渲染
+        assert!(scene.buffer().iter().all(|&v| v == 0));
+
+        scene.render();
+        assert_eq!(&target[..64], scene.buffer());
+
+        // 尺寸可以与场景不同
+        let mut small = vec![0u8; 3 * 3 * 4];
+        assert!(scene.render_into(&mut small, 3, 3).is_ok());
+        assert_eq!(&small[..4], &[255, 0, 0, 255]);
+        assert_eq!(&small[(2 * 3 + 2) * 4..], &[0, 0, 255, 255]);
+        assert_eq!((scene.width(), scene.height()), (4, 4));
+
+        let mut short = vec![0u8; 4 * 4 * 4 - 1];
+        let err = scene.render_into(&mut short, 4, 4).unwrap_err();
+        assert!(err.contains("too small"), "{err}");
+        assert!(short.iter().all(|&v| v == 0));
+
+        // 超采样路径同样使用目标尺寸
+        scene.set_supersample(2);
+        let mut large = vec![0u8; 6 * 6 * 4];
+        assert!(scene.render_into(&mut large, 6, 6).is_ok());
+        assert_eq!(&large[(5 * 6 + 5) * 4..], &[0, 0, 255, 255]);
+        assert_eq!((scene.width(), scene.height()), (4, 4));
+    }
+
+    #[test]
+    fn test_render_only_applies_post_processing() {
+        let build = || {
+            let mut scene = Scene::new(4, 4);
+            scene.set_global_opacity(0.5).set_output_premultiplied(true);
+            let mut sprite = ImageSprite::create_rectangle(4, 4, 200, 100, 0, 255);
+            sprite.set_anchor(0.0, 0.0);
+            let id = scene.add(sprite);
+            (scene, id)
+        };
+
+        let (mut expected, _) = build();
+        expected.render();
+        let (mut scene, id) = build();
+        scene.render_only(&[id]);
+        assert_eq!(scene.buffer(), expected.buffer());
+        assert_eq!(&scene.buffer()[..4], &[100, 50, 0, 128]);
+
+        // 关闭清屏时与 render 一样在现有 buffer 上再做一遍后处理
+        scene.set_clear_enabled(false);
+        scene.render_only(&[]);
+        assert_eq!(&scene.buffer()[..4], &[25, 13, 0, 64]);
+    }
 }
@@ -2,9 +2,66 @@
 //!
 //! 提供精灵 trait 和具体实现
 
+use crate::core::color::{LuminanceWeights, unpack_rgba};
+use crate::core::decode::{self, DecodeError};
 use crate::core::format::ImageFormat;
+use crate::core::sampling::{self, EdgeMode, SamplingMode};
 use crate::math::{Matrix3x3, MatrixOperations, Transform2D, Vec2};
 
+/// 混合模式
+///
+/// 决定精灵像素如何与目标 buffer 中已有的像素合成
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    /// 普通 Alpha 混合
+    Normal,
+    /// 叠加（颜色相加），适合发光、粒子效果
+    Additive,
+    /// 正片叠底（`src * dst / 255`），只会变暗，适合阴影
+    Multiply,
+    /// 滤色（`255 - (255 - src) * (255 - dst) / 255`），只会变亮
+    Screen,
+}
+
+/// 抗锯齿模式
+///
+/// 渲染时在每个目标像素内取多个子像素样本并取平均，平滑旋转、缩放后的边缘，
+/// 无需放大整个帧缓冲。样本越多越平滑，开销也随之增加
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AaMode {
+    /// 不做抗锯齿，只在像素中心取样
+    None,
+    /// 每像素 2 个样本（对角线）
+    Msaa2x,
+    /// 每像素 4 个样本（2x2 网格）
+    Msaa4x,
+}
+
+impl AaMode {
+    /// 目标像素内的子像素取样偏移
+    fn sample_offsets(self) -> &'static [(f32, f32)] {
+        match self {
+            AaMode::None => &[(0.5, 0.5)],
+            AaMode::Msaa2x => &[(0.25, 0.25), (0.75, 0.75)],
+            AaMode::Msaa4x => &[(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)],
+        }
+    }
+}
+
+impl BlendMode {
+    /// 对单个颜色通道应用混合公式（不含 alpha 加权）
+    ///
+    /// 只用于 `Multiply` 和 `Screen`，其余模式原样返回源值
+    #[inline]
+    pub(crate) fn blend_channel(self, src: f32, dst: f32) -> f32 {
+        match self {
+            BlendMode::Multiply => src * dst / 255.0,
+            BlendMode::Screen => 255.0 - (255.0 - src) * (255.0 - dst) / 255.0,
+            BlendMode::Normal | BlendMode::Additive => src,
+        }
+    }
+}
+
 /// 精灵 trait - 面向接口编程
 ///
 /// 所有可渲染的 2D 对象都应实现此 trait
@@ -15,12 +72,43 @@ pub trait Sprite {
     /// 获取精灵高度
     fn height(&self) -> u32;
 
+    /// 获取像素格式（默认 RGBA）
+    fn format(&self) -> ImageFormat {
+        ImageFormat::Rgba
+    }
+
     /// 获取 z-order 渲染层级
     fn z_order(&self) -> i32;
 
     /// 设置 z-order
     fn set_z_order(&mut self, z: i32);
 
+    /// 获取层内索引（z-order 相同时的次级排序键，默认 0）
+    fn layer_index(&self) -> u32 {
+        0
+    }
+
+    /// 获取混合模式（默认普通混合）
+    fn blend_mode(&self) -> BlendMode {
+        BlendMode::Normal
+    }
+
+    /// 获取不透明度（默认 1.0）
+    fn opacity(&self) -> f32 {
+        1.0
+    }
+
+    /// 设置不透明度，不支持的精灵忽略（默认不做处理）
+    fn set_opacity(&mut self, _opacity: f32) {}
+
+    /// 获取着色（RGBA 各通道乘数，默认全 1.0）
+    fn tint(&self) -> [f32; 4] {
+        [1.0; 4]
+    }
+
+    /// 设置着色，不支持的精灵忽略（默认不做处理）
+    fn set_tint(&mut self, _r: f32, _g: f32, _b: f32, _a: f32) {}
+
     /// 获取变换引用
     fn transform(&self) -> &Transform2D;
 
@@ -34,6 +122,22 @@ pub trait Sprite {
         self.transform_mut().matrix_with_size(w, h)
     }
 
+    /// 获取变换后的四个角点（顺序为 (0,0)、(w,0)、(w,h)、(0,h)）
+    ///
+    /// 可用于绘制与旋转/缩放后精灵贴合的选框
+    fn transformed_corners(&mut self) -> [Vec2; 4] {
+        let matrix = self.get_transform_matrix();
+        transformed_corners(&matrix, self.width() as f32, self.height() as f32)
+    }
+
+    /// 获取世界坐标下的轴对齐包围盒 (min, max)
+    ///
+    /// 只使用精灵自身的变换，不包含场景中的父级和相机变换
+    fn bounding_box(&mut self) -> (Vec2, Vec2) {
+        let matrix = self.get_transform_matrix();
+        transformed_bounds(&matrix, self.width() as f32, self.height() as f32)
+    }
+
     /// 渲染到目标 buffer
     ///
     /// # Arguments
@@ -42,8 +146,65 @@ pub trait Sprite {
     /// * `target_height` - 目标高度
     fn render_to(&mut self, target: &mut [u8], target_width: u32, target_height: u32);
 
+    /// 使用指定的变换矩阵渲染到目标 buffer
+    ///
+    /// 矩阵将精灵本地坐标映射到目标坐标，可用于叠加相机等额外变换。
+    /// 默认实现忽略 `matrix`，回退到 `render_to`（只使用精灵自身的变换），
+    /// 需要跟随父级和相机的精灵应覆盖此方法
+    ///
+    /// # Arguments
+    /// * `matrix` - 精灵本地坐标到目标坐标的完整变换
+    /// * `target` - 目标 buffer（RGBA 格式）
+    /// * `target_width` - 目标宽度
+    /// * `target_height` - 目标高度
+    fn render_with_matrix(
+        &mut self,
+        _matrix: &Matrix3x3,
+        target: &mut [u8],
+        target_width: u32,
+        target_height: u32,
+    ) {
+        self.render_to(target, target_width, target_height);
+    }
+
+    /// 使用指定的变换矩阵渲染，只写入裁剪矩形 (x, y, w, h) 内的像素
+    ///
+    /// `clip` 为 `None` 时等同于 `render_with_matrix`。默认实现先渲染到目标副本，
+    /// 再拷回裁剪区域；`ImageSprite` 会直接缩小扫描范围
+    fn render_with_matrix_clipped(
+        &mut self,
+        matrix: &Matrix3x3,
+        target: &mut [u8],
+        target_width: u32,
+        target_height: u32,
+        clip: Option<(u32, u32, u32, u32)>,
+    ) {
+        let Some(clip) = clip else {
+            self.render_with_matrix(matrix, target, target_width, target_height);
+            return;
+        };
+        let mut scratch = target.to_vec();
+        self.render_with_matrix(matrix, &mut scratch, target_width, target_height);
+        let (x0, y0, x1, y1) = clip_region(clip, target_width, target_height);
+        for y in y0..y1 {
+            let start = ((y * target_width + x0) * 4) as usize;
+            let end = ((y * target_width + x1) * 4) as usize;
+            target[start..end].copy_from_slice(&scratch[start..end]);
+        }
+    }
+
     /// 获取唯一标识符（用于精灵管理）
     fn id(&self) -> u64;
+
+    /// 尝试转换为 ImageSprite（用于访问图像精灵特有的操作）
+    fn as_image_sprite_mut(&mut self) -> Option<&mut ImageSprite> {
+        None
+    }
+
+    /// 获取本地像素坐标处的 alpha（用于拾取，默认完全不透明）
+    fn alpha_at(&self, _x: u32, _y: u32) -> u8 {
+        255
+    }
 }
 
 /// 图像精灵 - 持有图像数据的精灵
@@ -63,6 +224,26 @@ pub struct ImageSprite {
     transform: Transform2D,
     /// 渲染层级
     z_order: i32,
+    /// 层内索引（z-order 相同时的次级排序键）
+    layer_index: u32,
+    /// 混合模式
+    blend_mode: BlendMode,
+    /// 裁剪矩形 (x, y, w, h)，目标坐标系，超出部分不绘制
+    clip_rect: Option<(u32, u32, u32, u32)>,
+    /// 源矩形 (x, y, w, h)，只显示图像的这一部分（精灵图集中的一帧）
+    source_rect: Option<(u32, u32, u32, u32)>,
+    /// 渲染时的采样模式
+    sampling: SamplingMode,
+    /// 抗锯齿模式
+    aa_mode: AaMode,
+    /// 像素数据是否为预乘 alpha
+    premultiplied: bool,
+    /// 双线性插值邻居越界时的边缘处理方式
+    edge_mode: EdgeMode,
+    /// 不透明度（0.0-1.0），与像素 alpha 相乘
+    opacity: f32,
+    /// 着色（RGBA 各通道乘数，0.0-1.0）
+    tint: [f32; 4],
 }
 
 /// ID 生成器
@@ -79,31 +260,64 @@ fn generate_sprite_id() -> u64 {
 impl ImageSprite {
     /// 创建新的图像精灵
     ///
+    /// 宽或高为 0 时会被钳制为 1，避免产生无法渲染的空精灵
+    ///
     /// # Arguments
     /// * `width` - 图像宽度
     /// * `height` - 图像高度
     /// * `format` - 图像格式
+    ///
+    /// 所需字节数溢出或超过 4 GiB 时 panic，需要处理错误时使用 `try_new`
     pub fn new(width: u32, height: u32, format: ImageFormat) -> Self {
-        let size = (width * height * (format as u32)) as usize;
-        Self {
-            id: generate_sprite_id(),
-            buffer: vec![0; size],
-            width,
-            height,
-            format,
-            transform: Transform2D::new(),
-            z_order: 0,
-        }
+        Self::try_new(width, height, format).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// 创建新的图像精灵，所需字节数溢出或超过 4 GiB 时返回错误
+    pub fn try_new(width: u32, height: u32, format: ImageFormat) -> Result<Self, String> {
+        let width = width.max(1);
+        let height = height.max(1);
+        let size = format.buffer_size(width, height)?;
+        Ok(Self::with_pixels(vec![0; size], width, height, format))
     }
 
     /// 从现有数据创建图像精灵
     ///
+    /// 宽或高为 0 时会被钳制为 1，并用 0 补齐 buffer
+    ///
     /// # Arguments
     /// * `buffer` - 图像数据
     /// * `width` - 图像宽度
     /// * `height` - 图像高度
     /// * `format` - 图像格式
+    ///
+    /// 所需字节数溢出或超过 4 GiB 时 panic，需要处理错误时使用 `try_from_buffer`
     pub fn from_buffer(buffer: Vec<u8>, width: u32, height: u32, format: ImageFormat) -> Self {
+        Self::try_from_buffer(buffer, width, height, format).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// 从现有数据创建图像精灵，所需字节数溢出或超过 4 GiB 时返回错误
+    pub fn try_from_buffer(
+        mut buffer: Vec<u8>,
+        width: u32,
+        height: u32,
+        format: ImageFormat,
+    ) -> Result<Self, String> {
+        let size = format.buffer_size(width.max(1), height.max(1))?;
+        if width == 0 || height == 0 {
+            buffer.resize(size, 0);
+        }
+        Ok(Self::with_pixels(
+            buffer,
+            width.max(1),
+            height.max(1),
+            format,
+        ))
+    }
+
+    /// 用已校验的像素数据创建精灵，其余状态取默认值
+    ///
+    /// 所有构造函数都经由这里初始化字段，调用方负责保证尺寸非 0 且 buffer 长度匹配
+    fn with_pixels(buffer: Vec<u8>, width: u32, height: u32, format: ImageFormat) -> Self {
         Self {
             id: generate_sprite_id(),
             buffer,
@@ -112,9 +326,28 @@ impl ImageSprite {
             format,
             transform: Transform2D::new(),
             z_order: 0,
+            layer_index: 0,
+            blend_mode: BlendMode::Normal,
+            clip_rect: None,
+            source_rect: None,
+            sampling: SamplingMode::Nearest,
+            aa_mode: AaMode::None,
+            premultiplied: false,
+            edge_mode: EdgeMode::Clamp,
+            opacity: 1.0,
+            tint: [1.0; 4],
         }
     }
 
+    /// 从 PNG/JPEG 编码数据创建精灵
+    ///
+    /// 自动识别格式并设置宽高和像素格式（灰度、RGB 或 RGBA），
+    /// 解码后的尺寸超过 4 GiB 时返回 `DecodeError::Corrupt`
+    pub fn from_encoded(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let (buffer, width, height, format) = decode::decode(bytes)?;
+        Self::try_from_buffer(buffer, width, height, format).map_err(DecodeError::Corrupt)
+    }
+
     /// 获取 buffer 引用
     pub fn buffer(&self) -> &[u8] {
         &self.buffer
@@ -130,6 +363,178 @@ impl ImageSprite {
         self.format
     }
 
+    /// 原地替换像素数据
+    ///
+    /// 数据长度必须与精灵尺寸和格式一致，适合视频播放等每帧更新的场景
+    ///
+    /// # Arguments
+    /// * `data` - 新的像素数据
+    pub fn update_buffer(&mut self, data: &[u8]) -> Result<(), String> {
+        if data.len() != self.buffer.len() {
+            return Err(format!(
+                "buffer length {} does not match {}x{} {:?} (expected {})",
+                data.len(),
+                self.width,
+                self.height,
+                self.format,
+                self.buffer.len()
+            ));
+        }
+        self.buffer.copy_from_slice(data);
+        Ok(())
+    }
+
+    /// 设置层内索引
+    ///
+    /// z-order 相同时按层内索引从小到大渲染，不依赖添加顺序
+    pub fn set_layer_index(&mut self, index: u32) -> &mut Self {
+        self.layer_index = index;
+        self
+    }
+
+    /// 设置混合模式
+    pub fn set_blend_mode(&mut self, mode: BlendMode) -> &mut Self {
+        self.blend_mode = mode;
+        self
+    }
+
+    /// 设置裁剪矩形 (x, y, w, h)，`None` 表示不裁剪
+    ///
+    /// 坐标位于渲染目标坐标系，矩形外的像素不会被绘制
+    pub fn set_clip_rect(&mut self, rect: Option<(u32, u32, u32, u32)>) -> &mut Self {
+        self.clip_rect = rect;
+        self
+    }
+
+    /// 获取裁剪矩形
+    pub fn clip_rect(&self) -> Option<(u32, u32, u32, u32)> {
+        self.clip_rect
+    }
+
+    /// 设置不透明度（钳制到 0.0-1.0），渲染时与像素 alpha 相乘
+    pub fn set_opacity(&mut self, opacity: f32) -> &mut Self {
+        self.opacity = if opacity.is_nan() {
+            1.0
+        } else {
+            opacity.clamp(0.0, 1.0)
+        };
+        self
+    }
+
+    /// 获取不透明度
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// 设置着色，渲染时每个通道乘以对应系数（钳制到 0.0-1.0，默认全 1.0）
+    ///
+    /// 可用同一张白色纹理绘制不同颜色的精灵，无需复制像素数据
+    pub fn set_tint(&mut self, r: f32, g: f32, b: f32, a: f32) -> &mut Self {
+        let clamp = |v: f32| if v.is_nan() { 1.0 } else { v.clamp(0.0, 1.0) };
+        self.tint = [clamp(r), clamp(g), clamp(b), clamp(a)];
+        self
+    }
+
+    /// 获取着色
+    pub fn tint(&self) -> [f32; 4] {
+        self.tint
+    }
+
+    /// 设置源矩形，只显示图像的 (x, y, w, h) 部分，用于精灵图集动画
+    ///
+    /// 矩形会被钳制到图像范围内（宽高至少为 1）。设置后变换、包围盒等使用的
+    /// 宽高都变为源矩形的尺寸
+    pub fn set_source_rect(&mut self, x: u32, y: u32, w: u32, h: u32) -> &mut Self {
+        let x = x.min(self.width - 1);
+        let y = y.min(self.height - 1);
+        let w = w.clamp(1, self.width - x);
+        let h = h.clamp(1, self.height - y);
+        self.source_rect = Some((x, y, w, h));
+        self
+    }
+
+    /// 清除源矩形，恢复显示完整图像
+    pub fn clear_source_rect(&mut self) -> &mut Self {
+        self.source_rect = None;
+        self
+    }
+
+    /// 获取源矩形
+    pub fn source_rect(&self) -> Option<(u32, u32, u32, u32)> {
+        self.source_rect
+    }
+
+    /// 实际显示的图像区域 (x, y, w, h)
+    fn source_region(&self) -> (u32, u32, u32, u32) {
+        self.source_rect.unwrap_or((0, 0, self.width, self.height))
+    }
+
+    /// 设置渲染采样模式（默认最近邻）
+    ///
+    /// 像素画使用 `Nearest` 保持边缘清晰，缩放照片使用 `Bilinear` 获得平滑过渡
+    pub fn set_sampling_mode(&mut self, mode: SamplingMode) -> &mut Self {
+        self.sampling = mode;
+        self
+    }
+
+    /// 获取渲染采样模式
+    pub fn sampling_mode(&self) -> SamplingMode {
+        self.sampling
+    }
+
+    /// 设置抗锯齿模式（默认 `None`，输出与逐像素中心取样一致）
+    pub fn set_aa_mode(&mut self, mode: AaMode) -> &mut Self {
+        self.aa_mode = mode;
+        self
+    }
+
+    /// 获取抗锯齿模式
+    pub fn aa_mode(&self) -> AaMode {
+        self.aa_mode
+    }
+
+    /// 设置像素数据是否为预乘 alpha（默认否）
+    ///
+    /// 开启后颜色通道视为已乘过 alpha，普通混合按 `dst = src + dst * (1 - a)` 合成。
+    /// 带透明边框的图像缩放时，预乘数据插值不会把透明像素的黑色混入边缘
+    pub fn set_premultiplied(&mut self, premultiplied: bool) -> &mut Self {
+        self.premultiplied = premultiplied;
+        self
+    }
+
+    /// 像素数据是否为预乘 alpha
+    pub fn premultiplied(&self) -> bool {
+        self.premultiplied
+    }
+
+    /// 设置双线性插值的边缘处理方式（默认钳制）
+    ///
+    /// 平铺纹理使用 `Mirror`，避免边缘与越界的钳制邻居混合
+    pub fn set_edge_mode(&mut self, mode: EdgeMode) -> &mut Self {
+        self.edge_mode = mode;
+        self
+    }
+
+    /// 获取边缘处理方式
+    pub fn edge_mode(&self) -> EdgeMode {
+        self.edge_mode
+    }
+
+    /// 重置渲染状态（混合模式、裁剪矩形、采样、不透明度、着色、抗锯齿、预乘）为默认值
+    ///
+    /// 不影响变换和像素数据，适合复用对象池中的精灵
+    pub fn reset_render_state(&mut self) -> &mut Self {
+        self.blend_mode = BlendMode::Normal;
+        self.clip_rect = None;
+        self.sampling = SamplingMode::Nearest;
+        self.edge_mode = EdgeMode::Clamp;
+        self.opacity = 1.0;
+        self.tint = [1.0; 4];
+        self.aa_mode = AaMode::None;
+        self.premultiplied = false;
+        self
+    }
+
     // ===== 变换操作便捷方法 =====
 
     /// 设置位置
@@ -168,6 +573,18 @@ impl ImageSprite {
         self
     }
 
+    /// 设置水平翻转（绕精灵自身区域镜像，位置不变）
+    pub fn set_flip_x(&mut self, flip: bool) -> &mut Self {
+        self.transform.set_flip_x(flip);
+        self
+    }
+
+    /// 设置垂直翻转（绕精灵自身区域镜像，位置不变）
+    pub fn set_flip_y(&mut self, flip: bool) -> &mut Self {
+        self.transform.set_flip_y(flip);
+        self
+    }
+
     /// 平移
     pub fn translate(&mut self, dx: f32, dy: f32) -> &mut Self {
         self.transform.translate(dx, dy);
@@ -188,7 +605,7 @@ impl ImageSprite {
 
     /// 创建长方形精灵
     ///
-    /// 创建一个填充指定颜色的长方形精灵
+    /// 创建一个填充指定颜色的长方形精灵，宽或高为 0 时会被钳制为 1
     ///
     /// # Arguments
     /// * `width` - 长方形宽度
@@ -198,7 +615,11 @@ impl ImageSprite {
     /// * `b` - 蓝色分量 (0-255)
     /// * `a` - 透明度分量 (0-255)
     pub fn create_rectangle(width: u32, height: u32, r: u8, g: u8, b: u8, a: u8) -> Self {
-        let size = (width * height * 4) as usize;
+        let width = width.max(1);
+        let height = height.max(1);
+        let size = ImageFormat::Rgba
+            .buffer_size(width, height)
+            .unwrap_or_else(|e| panic!("{e}"));
         let mut buffer = vec![0u8; size];
 
         // 填充颜色
@@ -209,15 +630,7 @@ impl ImageSprite {
             buffer[i + 3] = a;
         }
 
-        Self {
-            id: generate_sprite_id(),
-            buffer,
-            width,
-            height,
-            format: ImageFormat::Rgba,
-            transform: Transform2D::new(),
-            z_order: 0,
-        }
+        Self::with_pixels(buffer, width, height, ImageFormat::Rgba)
     }
 
     /// 创建长方形精灵（十六进制颜色）
@@ -227,13 +640,227 @@ impl ImageSprite {
     /// * `height` - 长方形高度
     /// * `color` - 颜色值 (0xRRGGBBAA)
     pub fn create_rectangle_hex(width: u32, height: u32, color: u32) -> Self {
-        let r = ((color >> 24) & 0xFF) as u8;
-        let g = ((color >> 16) & 0xFF) as u8;
-        let b = ((color >> 8) & 0xFF) as u8;
-        let a = (color & 0xFF) as u8;
+        let (r, g, b, a) = unpack_rgba(color);
         Self::create_rectangle(width, height, r, g, b, a)
     }
 
+    /// 创建圆形精灵
+    ///
+    /// 缓冲区为 `2r x 2r` 的 RGBA，圆外像素全透明，边缘按覆盖率做 1 像素抗锯齿。
+    /// 半径为 0 时按 1 处理
+    ///
+    /// # Arguments
+    /// * `radius` - 半径（像素）
+    /// * `r` / `g` / `b` / `a` - 颜色分量 (0-255)
+    ///
+    /// 直径溢出或所需字节数超过 4 GiB 时 panic，需要处理错误时使用 `try_create_circle`
+    pub fn create_circle(radius: u32, r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::try_create_circle(radius, r, g, b, a).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// 创建圆形精灵，直径溢出或所需字节数超过 4 GiB 时返回错误
+    pub fn try_create_circle(radius: u32, r: u8, g: u8, b: u8, a: u8) -> Result<Self, String> {
+        let radius = radius.max(1);
+        let diameter = radius
+            .checked_mul(2)
+            .ok_or_else(|| format!("circle radius {} is too large", radius))?;
+        let center = radius as f32;
+        Self::from_coverage(diameter, diameter, [r, g, b, a], |x, y| {
+            let (dx, dy) = (x - center, y - center);
+            center - (dx * dx + dy * dy).sqrt()
+        })
+    }
+
+    /// 创建圆角矩形精灵
+    ///
+    /// 四角为四分之一圆，圆角边缘按覆盖率抗锯齿。
+    /// `corner_radius` 超过 `min(width, height) / 2` 时被钳制
+    ///
+    /// # Arguments
+    /// * `width` / `height` - 矩形尺寸（0 按 1 处理）
+    /// * `corner_radius` - 圆角半径（像素）
+    /// * `r` / `g` / `b` / `a` - 颜色分量 (0-255)
+    ///
+    /// 所需字节数溢出或超过 4 GiB 时 panic，需要处理错误时使用 `try_create_rounded_rectangle`
+    pub fn create_rounded_rectangle(
+        width: u32,
+        height: u32,
+        corner_radius: f32,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) -> Self {
+        Self::try_create_rounded_rectangle(width, height, corner_radius, r, g, b, a)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// 创建圆角矩形精灵，所需字节数溢出或超过 4 GiB 时返回错误
+    pub fn try_create_rounded_rectangle(
+        width: u32,
+        height: u32,
+        corner_radius: f32,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) -> Result<Self, String> {
+        let (width, height) = (width.max(1), height.max(1));
+        let (half_w, half_h) = (width as f32 / 2.0, height as f32 / 2.0);
+        let radius = corner_radius.clamp(0.0, half_w.min(half_h));
+
+        Self::from_coverage(width, height, [r, g, b, a], |x, y| {
+            // 圆角矩形的有符号距离场（取负使内部为正）
+            let qx = (x - half_w).abs() - (half_w - radius);
+            let qy = (y - half_h).abs() - (half_h - radius);
+            let outside = Vec2::new(qx.max(0.0), qy.max(0.0)).length();
+            let inside = qx.max(qy).min(0.0);
+            radius - outside - inside
+        })
+    }
+
+    /// 按覆盖率填充单色 RGBA 精灵
+    ///
+    /// `signed_distance` 接收像素中心坐标，返回到形状边缘的有符号距离（内部为正），
+    /// 距离在 ±0.5 之间的像素按比例降低 alpha。所需字节数溢出或超过 4 GiB 时返回错误
+    fn from_coverage(
+        width: u32,
+        height: u32,
+        [r, g, b, a]: [u8; 4],
+        signed_distance: impl Fn(f32, f32) -> f32,
+    ) -> Result<Self, String> {
+        let size = ImageFormat::Rgba.buffer_size(width, height)?;
+        let mut buffer = Vec::with_capacity(size);
+        for y in 0..height {
+            for x in 0..width {
+                let coverage =
+                    (signed_distance(x as f32 + 0.5, y as f32 + 0.5) + 0.5).clamp(0.0, 1.0);
+                let alpha = (a as f32 * coverage).round() as u8;
+                if alpha == 0 {
+                    buffer.extend_from_slice(&[0, 0, 0, 0]);
+                } else {
+                    buffer.extend_from_slice(&[r, g, b, alpha]);
+                }
+            }
+        }
+        Self::try_from_buffer(buffer, width, height, ImageFormat::Rgba)
+    }
+
+    /// 应用颜色键透明
+    ///
+    /// 将与 `color` 的 RGB 分量差值都不超过 `tolerance` 的像素 alpha 置为 0，
+    /// 常用于没有 Alpha 通道的图像（如经典的洋红色背景）。
+    /// 非 RGBA 格式的精灵会先转换为 RGBA。
+    ///
+    /// # Arguments
+    /// * `color` - 键颜色 (0xRRGGBBAA，忽略 alpha)
+    /// * `tolerance` - 每个通道允许的最大差值
+    pub fn with_color_key(mut self, color: u32, tolerance: u8) -> Self {
+        let (r, g, b, _) = unpack_rgba(color);
+        let key = [r, g, b];
+
+        if self.format != ImageFormat::Rgba {
+            self.buffer = self.to_rgba_buffer();
+            self.format = ImageFormat::Rgba;
+        }
+
+        for pixel in self.buffer.chunks_exact_mut(4) {
+            let matches = pixel[..3]
+                .iter()
+                .zip(key.iter())
+                .all(|(&c, &k)| c.abs_diff(k) <= tolerance);
+            if matches {
+                pixel[3] = 0;
+            }
+        }
+        self
+    }
+
+    /// 计算渲染时需要扫描的目标区域 (x0, y0, x1, y1)，右、下边界不包含
+    ///
+    /// 取变换后包围盒、裁剪矩形与目标范围三者的交集，交集为空时返回 `None`
+    fn scan_region(
+        &self,
+        matrix: &Matrix3x3,
+        target_width: u32,
+        target_height: u32,
+    ) -> Option<(u32, u32, u32, u32)> {
+        let (_, _, w, h) = self.source_region();
+        let (min, max) = transformed_bounds(matrix, w as f32, h as f32);
+
+        // 包围盒向外取整，多留一像素避免浮点误差漏掉边缘
+        let clamp_x = |v: f32| v.clamp(0.0, target_width as f32) as u32;
+        let clamp_y = |v: f32| v.clamp(0.0, target_height as f32) as u32;
+        let mut x0 = clamp_x(min.x.floor());
+        let mut y0 = clamp_y(min.y.floor());
+        let mut x1 = clamp_x(max.x.ceil() + 1.0);
+        let mut y1 = clamp_y(max.y.ceil() + 1.0);
+
+        if let Some((cx, cy, cw, ch)) = self.clip_rect {
+            x0 = x0.max(cx);
+            y0 = y0.max(cy);
+            x1 = x1.min(cx.saturating_add(cw));
+            y1 = y1.min(cy.saturating_add(ch));
+        }
+
+        if x0 < x1 && y0 < y1 {
+            Some((x0, y0, x1, y1))
+        } else {
+            None
+        }
+    }
+
+    /// 按行优先顺序遍历所有像素，产出 (x, y, RGBA)
+    ///
+    /// 非 RGBA 格式会展开为 RGBA，便于编写自定义逐像素处理
+    pub fn pixels(&self) -> impl Iterator<Item = (u32, u32, [u8; 4])> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y, self.get_pixel_rgba(x, y))))
+    }
+
+    /// 生成 RGBA 格式的像素数据副本
+    ///
+    /// 无论精灵原始格式如何，都按 RGBA 逐像素展开，便于导出或拷贝
+    pub fn to_rgba_buffer(&self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(self.width as usize * self.height as usize * 4);
+        for (_, _, pixel) in self.pixels() {
+            rgba.extend_from_slice(&pixel);
+        }
+        rgba
+    }
+
+    /// 按当前采样模式在连续坐标 (x, y) 处取样，返回 RGBA
+    ///
+    /// 坐标相对于源矩形原点，像素 `i` 的中心位于 `i + 0.5`；
+    /// 双线性插值的越界邻居按边缘处理方式取值，不会环绕，也不会读到源矩形之外
+    fn sample_rgba(&self, x: f32, y: f32) -> [u8; 4] {
+        let (rx, ry, rw, rh) = self.source_region();
+        if self.sampling == SamplingMode::Nearest {
+            let px = (x.max(0.0) as u32).min(rw - 1);
+            let py = (y.max(0.0) as u32).min(rh - 1);
+            return self.get_pixel_rgba(rx + px, ry + py);
+        }
+
+        let channels = self.format as usize;
+        let mut out = [0u8; 4];
+        sampling::sample_with_edge(
+            &self.buffer,
+            self.width,
+            (rx, ry, rw, rh),
+            channels,
+            x,
+            y,
+            SamplingMode::Bilinear,
+            self.edge_mode,
+            &mut out,
+        );
+        match self.format {
+            ImageFormat::Rgba => out,
+            ImageFormat::Rgb => [out[0], out[1], out[2], 255],
+            ImageFormat::Grayscale => [out[0], out[0], out[0], 255],
+        }
+    }
+
     /// 获取指定位置的像素（转换为 RGBA）
     fn get_pixel_rgba(&self, x: u32, y: u32) -> [u8; 4] {
         let idx = (y * self.width + x) as usize;
@@ -265,12 +892,18 @@ impl ImageSprite {
 }
 
 impl Sprite for ImageSprite {
+    /// 设置了源矩形时返回源矩形宽度
     fn width(&self) -> u32 {
-        self.width
+        self.source_region().2
     }
 
+    /// 设置了源矩形时返回源矩形高度
     fn height(&self) -> u32 {
-        self.height
+        self.source_region().3
+    }
+
+    fn format(&self) -> ImageFormat {
+        ImageSprite::format(self)
     }
 
     fn z_order(&self) -> i32 {
@@ -281,6 +914,30 @@ impl Sprite for ImageSprite {
         self.z_order = z;
     }
 
+    fn layer_index(&self) -> u32 {
+        self.layer_index
+    }
+
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    fn set_opacity(&mut self, opacity: f32) {
+        ImageSprite::set_opacity(self, opacity);
+    }
+
+    fn tint(&self) -> [f32; 4] {
+        self.tint
+    }
+
+    fn set_tint(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        ImageSprite::set_tint(self, r, g, b, a);
+    }
+
     fn transform(&self) -> &Transform2D {
         &self.transform
     }
@@ -293,71 +950,666 @@ impl Sprite for ImageSprite {
         self.id
     }
 
+    fn as_image_sprite_mut(&mut self) -> Option<&mut ImageSprite> {
+        Some(self)
+    }
+
+    fn alpha_at(&self, x: u32, y: u32) -> u8 {
+        let (rx, ry, rw, rh) = self.source_region();
+        if x >= rw || y >= rh {
+            return 0;
+        }
+        self.get_pixel_rgba(rx + x, ry + y)[3]
+    }
+
     fn render_to(&mut self, target: &mut [u8], target_width: u32, target_height: u32) {
         let matrix = self.get_transform_matrix();
+        self.render_with_matrix(&matrix, target, target_width, target_height);
+    }
+
+    fn render_with_matrix(
+        &mut self,
+        matrix: &Matrix3x3,
+        target: &mut [u8],
+        target_width: u32,
+        target_height: u32,
+    ) {
+        self.render_with_matrix_format(
+            matrix,
+            target,
+            target_width,
+            target_height,
+            ImageFormat::Rgba,
+            None,
+        );
+    }
+
+    fn render_with_matrix_clipped(
+        &mut self,
+        matrix: &Matrix3x3,
+        target: &mut [u8],
+        target_width: u32,
+        target_height: u32,
+        clip: Option<(u32, u32, u32, u32)>,
+    ) {
+        self.render_with_matrix_format(
+            matrix,
+            target,
+            target_width,
+            target_height,
+            ImageFormat::Rgba,
+            clip,
+        );
+    }
+}
+
+impl ImageSprite {
+    /// 渲染到指定格式的目标 buffer（如 RGB 或灰度的 `SharedBuffer`）
+    ///
+    /// 目标像素先展开为 RGBA（alpha 视为 255）再混合，写回时 RGB 丢弃 alpha，
+    /// 灰度按 Rec. 601 亮度换算。目标长度与尺寸、格式不符时不做渲染
+    pub fn render_to_format(
+        &mut self,
+        target: &mut [u8],
+        target_width: u32,
+        target_height: u32,
+        target_format: ImageFormat,
+    ) {
+        let matrix = self.get_transform_matrix();
+        self.render_with_matrix_format(
+            &matrix,
+            target,
+            target_width,
+            target_height,
+            target_format,
+            None,
+        );
+    }
+
+    fn render_with_matrix_format(
+        &mut self,
+        matrix: &Matrix3x3,
+        target: &mut [u8],
+        target_width: u32,
+        target_height: u32,
+        target_format: ImageFormat,
+        clip: Option<(u32, u32, u32, u32)>,
+    ) {
+        // 目标长度必须与尺寸、格式一致，否则跳过渲染，避免越界写入
+        let bpp = target_format.bytes_per_pixel();
+        if target.len() != target_width as usize * target_height as usize * bpp {
+            return;
+        }
+
+        // 着色 alpha 与不透明度合并为一个系数；完全透明时无需逐像素处理
+        let [tint_r, tint_g, tint_b, tint_a] = self.tint;
+        let alpha_scale = tint_a * self.opacity;
+        if alpha_scale <= 0.0 {
+            return;
+        }
+        let tinted = self.tint != [1.0; 4];
+
         let inv_matrix = match matrix.inverse() {
             Some(inv) => inv,
             None => return, // 矩阵不可逆，跳过渲染
         };
 
-        let sprite_w = self.width as f32;
-        let sprite_h = self.height as f32;
+        // 只遍历包围盒、裁剪矩形与目标范围的交集
+        let Some((mut x0, mut y0, mut x1, mut y1)) =
+            self.scan_region(matrix, target_width, target_height)
+        else {
+            return;
+        };
+        if let Some(clip) = clip {
+            let (cx0, cy0, cx1, cy1) = clip_region(clip, target_width, target_height);
+            (x0, y0, x1, y1) = (x0.max(cx0), y0.max(cy0), x1.min(cx1), y1.min(cy1));
+        }
 
         // 遍历目标像素
-        for ty in 0..target_height {
-            for tx in 0..target_width {
-                // 逆变换获取源坐标
-                let target_point = Vec2::new(tx as f32, ty as f32);
-                let source_point = inv_matrix.transform_point(target_point);
-
-                let sx = source_point.x;
-                let sy = source_point.y;
-
-                // 边界检查
-                if sx >= 0.0 && sx < sprite_w && sy >= 0.0 && sy < sprite_h {
-                    let src_x = sx as u32;
-                    let src_y = sy as u32;
-
-                    let pixel = self.get_pixel_rgba(src_x, src_y);
-
-                    // Alpha 混合
-                    let alpha = pixel[3] as f32 / 255.0;
-                    if alpha > 0.0 {
-                        let target_idx = ((ty * target_width + tx) * 4) as usize;
-
-                        if alpha >= 1.0 {
-                            // 完全不透明，直接覆盖
-                            target[target_idx] = pixel[0];
-                            target[target_idx + 1] = pixel[1];
-                            target[target_idx + 2] = pixel[2];
-                            target[target_idx + 3] = 255;
-                        } else {
-                            // Alpha 混合
-                            let inv_alpha = 1.0 - alpha;
-                            target[target_idx] = (pixel[0] as f32 * alpha
-                                + target[target_idx] as f32 * inv_alpha)
-                                as u8;
-                            target[target_idx + 1] = (pixel[1] as f32 * alpha
-                                + target[target_idx + 1] as f32 * inv_alpha)
-                                as u8;
-                            target[target_idx + 2] = (pixel[2] as f32 * alpha
-                                + target[target_idx + 2] as f32 * inv_alpha)
-                                as u8;
-                            target[target_idx + 3] = ((alpha
-                                + target[target_idx + 3] as f32 / 255.0 * inv_alpha)
-                                * 255.0) as u8;
-                        }
+        let offsets = self.aa_mode.sample_offsets();
+        for ty in y0..y1 {
+            for tx in x0..x1 {
+                // 逆变换获取源坐标（按子像素偏移取样，未开启抗锯齿时只取像素中心）
+                let Some(mut pixel) = self.sample_coverage(&inv_matrix, tx, ty, offsets) else {
+                    continue;
+                };
+                if tinted {
+                    pixel[0] = (pixel[0] as f32 * tint_r).round() as u8;
+                    pixel[1] = (pixel[1] as f32 * tint_g).round() as u8;
+                    pixel[2] = (pixel[2] as f32 * tint_b).round() as u8;
+                }
+                if alpha_scale < 1.0 {
+                    // 预乘数据的颜色通道同样要乘以不透明度
+                    let channels = if self.premultiplied { 0..4 } else { 3..4 };
+                    for c in &mut pixel[channels] {
+                        *c = (*c as f32 * alpha_scale).round() as u8;
+                    }
+                }
+                let blend = |dst: &mut [u8]| {
+                    if self.premultiplied {
+                        blend_premultiplied_pixel(dst, pixel, self.blend_mode);
+                    } else {
+                        blend_pixel(dst, pixel, self.blend_mode);
+                    }
+                };
+                let target_idx = (ty * target_width + tx) as usize * bpp;
+                let dst = &mut target[target_idx..target_idx + bpp];
+                match target_format {
+                    ImageFormat::Rgba => blend(dst),
+                    ImageFormat::Rgb => {
+                        let mut rgba = [dst[0], dst[1], dst[2], 255];
+                        blend(&mut rgba);
+                        dst.copy_from_slice(&rgba[..3]);
+                    }
+                    ImageFormat::Grayscale => {
+                        let mut rgba = [dst[0], dst[0], dst[0], 255];
+                        blend(&mut rgba);
+                        dst[0] = LuminanceWeights::default().luminance(rgba[0], rgba[1], rgba[2]);
                     }
                 }
             }
         }
     }
+
+    /// 在目标像素 (tx, ty) 内按子像素偏移取样并取平均，所有样本都落在精灵外时返回 `None`
+    ///
+    /// 只有一个样本时直接返回取样结果；多个样本时颜色按 alpha 加权平均（预乘数据直接平均），
+    /// 落在精灵外的样本视为完全透明，边缘像素因此得到中间 alpha
+    fn sample_coverage(
+        &self,
+        inv_matrix: &Matrix3x3,
+        tx: u32,
+        ty: u32,
+        offsets: &[(f32, f32)],
+    ) -> Option<[u8; 4]> {
+        let (_, _, w, h) = self.source_region();
+        let (sprite_w, sprite_h) = (w as f32, h as f32);
+        let sample_at = |(ox, oy): (f32, f32)| {
+            let source_point =
+                inv_matrix.transform_point(Vec2::new(tx as f32 + ox, ty as f32 + oy));
+            let (sx, sy) = (source_point.x, source_point.y);
+            // 边界检查
+            (sx >= 0.0 && sx < sprite_w && sy >= 0.0 && sy < sprite_h)
+                .then(|| self.sample_rgba(sx, sy))
+        };
+
+        if let [offset] = offsets {
+            return sample_at(*offset);
+        }
+
+        let mut hits = 0;
+        let mut color = [0.0f32; 3];
+        let mut alpha = 0.0f32;
+        for pixel in offsets.iter().filter_map(|&offset| sample_at(offset)) {
+            hits += 1;
+            let a = pixel[3] as f32;
+            let weight = if self.premultiplied { 1.0 } else { a };
+            for (acc, &c) in color.iter_mut().zip(&pixel[..3]) {
+                *acc += c as f32 * weight;
+            }
+            alpha += a;
+        }
+        if hits == 0 {
+            return None;
+        }
+
+        let mut result = [0u8; 4];
+        if self.premultiplied {
+            // 预乘数据本身已按 alpha 加权，落在外面的样本为全 0
+            let count = offsets.len() as f32;
+            for (out, acc) in result.iter_mut().zip(color) {
+                *out = (acc / count).round() as u8;
+            }
+        } else if alpha > 0.0 {
+            for (out, acc) in result.iter_mut().zip(color) {
+                *out = (acc / alpha).round() as u8;
+            }
+        }
+        result[3] = (alpha / offsets.len() as f32).round() as u8;
+        Some(result)
+    }
+}
+
+/// 将裁剪矩形 (x, y, w, h) 限制到目标范围内，返回 (x0, y0, x1, y1)，右、下边界不包含
+pub(crate) fn clip_region(
+    clip: (u32, u32, u32, u32),
+    target_width: u32,
+    target_height: u32,
+) -> (u32, u32, u32, u32) {
+    let (x, y, w, h) = clip;
+    let x0 = x.min(target_width);
+    let y0 = y.min(target_height);
+    let x1 = x.saturating_add(w).min(target_width);
+    let y1 = y.saturating_add(h).min(target_height);
+    (x0, y0, x1, y1)
+}
+
+/// 计算 `w x h` 矩形的四个角点经矩阵变换后的位置
+pub(crate) fn transformed_corners(matrix: &Matrix3x3, w: f32, h: f32) -> [Vec2; 4] {
+    [
+        matrix.transform_point(Vec2::new(0.0, 0.0)),
+        matrix.transform_point(Vec2::new(w, 0.0)),
+        matrix.transform_point(Vec2::new(w, h)),
+        matrix.transform_point(Vec2::new(0.0, h)),
+    ]
+}
+
+/// 计算 `w x h` 矩形经矩阵变换后的轴对齐包围盒 (min, max)
+pub(crate) fn transformed_bounds(matrix: &Matrix3x3, w: f32, h: f32) -> (Vec2, Vec2) {
+    let corners = transformed_corners(matrix, w, h);
+    corners[1..]
+        .iter()
+        .fold((corners[0], corners[0]), |(min, max), corner| {
+            (min.min(corner), max.max(corner))
+        })
+}
+
+/// 将一个 RGBA 像素按混合模式合成到目标像素上
+///
+/// # Arguments
+/// * `dst` - 目标像素（RGBA，4 字节）
+/// * `pixel` - 源像素（RGBA）
+/// * `mode` - 混合模式
+pub(crate) fn blend_pixel(dst: &mut [u8], pixel: [u8; 4], mode: BlendMode) {
+    let alpha = pixel[3] as f32 / 255.0;
+    if alpha <= 0.0 {
+        return;
+    }
+
+    match mode {
+        BlendMode::Normal => {
+            if alpha >= 1.0 {
+                // 完全不透明，直接覆盖
+                dst[0] = pixel[0];
+                dst[1] = pixel[1];
+                dst[2] = pixel[2];
+                dst[3] = 255;
+            } else {
+                // Alpha 混合
+                let inv_alpha = 1.0 - alpha;
+                dst[0] = (pixel[0] as f32 * alpha + dst[0] as f32 * inv_alpha) as u8;
+                dst[1] = (pixel[1] as f32 * alpha + dst[1] as f32 * inv_alpha) as u8;
+                dst[2] = (pixel[2] as f32 * alpha + dst[2] as f32 * inv_alpha) as u8;
+                dst[3] = ((alpha + dst[3] as f32 / 255.0 * inv_alpha) * 255.0) as u8;
+            }
+        }
+        BlendMode::Additive => {
+            // 叠加：按 alpha 加权后与目标相加，饱和到 255
+            for c in 0..3 {
+                dst[c] = (dst[c] as f32 + pixel[c] as f32 * alpha).min(255.0) as u8;
+            }
+            dst[3] = dst[3].saturating_add(pixel[3]);
+        }
+        BlendMode::Multiply | BlendMode::Screen => {
+            // 先按公式得到混合色，再按源 alpha 与目标插值
+            let inv_alpha = 1.0 - alpha;
+            for c in 0..3 {
+                let d = dst[c] as f32;
+                let blended = mode.blend_channel(pixel[c] as f32, d);
+                dst[c] = (blended * alpha + d * inv_alpha).round() as u8;
+            }
+            dst[3] = ((alpha + dst[3] as f32 / 255.0 * inv_alpha) * 255.0) as u8;
+        }
+    }
+}
+
+/// 将一个预乘 alpha 的 RGBA 像素按混合模式合成到目标像素上
+///
+/// 普通混合为 `dst = src + dst * (1 - a)`，叠加直接相加颜色；
+/// 正片叠底和滤色先还原为非预乘颜色再套用 `blend_pixel`
+fn blend_premultiplied_pixel(dst: &mut [u8], pixel: [u8; 4], mode: BlendMode) {
+    let alpha = pixel[3] as f32 / 255.0;
+    match mode {
+        BlendMode::Normal => {
+            let inv_alpha = 1.0 - alpha;
+            for c in 0..3 {
+                dst[c] = (pixel[c] as f32 + dst[c] as f32 * inv_alpha)
+                    .round()
+                    .min(255.0) as u8;
+            }
+            dst[3] = (pixel[3] as f32 + dst[3] as f32 * inv_alpha).round() as u8;
+        }
+        BlendMode::Additive => {
+            for c in 0..3 {
+                dst[c] = dst[c].saturating_add(pixel[c]);
+            }
+            dst[3] = dst[3].saturating_add(pixel[3]);
+        }
+        BlendMode::Multiply | BlendMode::Screen => {
+            if alpha <= 0.0 {
+                return;
+            }
+            let mut straight = pixel;
+            for c in &mut straight[..3] {
+                *c = (*c as f32 / alpha).round().min(255.0) as u8;
+            }
+            blend_pixel(dst, straight, mode);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// 灰色精灵 (100) 以指定模式合成到灰色背景 (200) 上，返回结果的 R 通道
+    fn composite_gray(mode: BlendMode) -> u8 {
+        let mut sprite = ImageSprite::create_rectangle(1, 1, 100, 100, 100, 255);
+        sprite.set_anchor(0.0, 0.0).set_blend_mode(mode);
+        let mut target = vec![200, 200, 200, 255];
+        sprite.render_to(&mut target, 1, 1);
+        target[0]
+    }
+
+    #[test]
+    fn test_render_to_rgb_and_grayscale_targets() {
+        let mut sprite = ImageSprite::create_rectangle(1, 1, 255, 0, 0, 255);
+        sprite.set_anchor(0.0, 0.0).set_position(1.0, 0.0);
+
+        let mut rgb = vec![0, 0, 255, 0, 0, 255, 0, 0, 255];
+        sprite.render_to_format(&mut rgb, 3, 1, ImageFormat::Rgb);
+        assert_eq!(rgb, vec![0, 0, 255, 255, 0, 0, 0, 0, 255]);
+
+        // 半透明红色混合到蓝色上
+        sprite.set_opacity(0.5);
+        let mut rgb = vec![0, 0, 255, 0, 0, 255, 0, 0, 255];
+        sprite.render_to_format(&mut rgb, 3, 1, ImageFormat::Rgb);
+        assert_eq!(&rgb[3..6], &[128, 0, 126]);
+        assert_eq!(&rgb[..3], &[0, 0, 255]);
+
+        sprite.set_opacity(1.0);
+        let mut gray = vec![10; 3];
+        sprite.render_to_format(&mut gray, 3, 1, ImageFormat::Grayscale);
+        assert_eq!(gray, vec![10, 76, 10]);
+
+        // 长度与格式不符时不渲染
+        let mut wrong = vec![0; 3 * 4];
+        sprite.render_to_format(&mut wrong, 3, 1, ImageFormat::Rgb);
+        assert!(wrong.iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    fn test_create_circle() {
+        let circle = ImageSprite::create_circle(8, 255, 0, 0, 200);
+        assert_eq!((circle.width(), circle.height()), (16, 16));
+
+        let alpha = |x: u32, y: u32| circle.buffer()[((y * 16 + x) * 4 + 3) as usize];
+        assert_eq!(alpha(8, 8), 200);
+        assert_eq!(alpha(7, 7), 200);
+        assert_eq!(&circle.buffer()[(8 * 16 + 8) * 4..][..3], &[255, 0, 0]);
+        // 四角在圆外，全透明
+        for (x, y) in [(0, 0), (15, 0), (0, 15), (15, 15)] {
+            assert_eq!(alpha(x, y), 0);
+        }
+        // 边缘像素部分覆盖
+        assert!((0..16).any(|x| (1..200).contains(&alpha(x, 2))));
+
+        // 直径溢出或尺寸过大时返回错误而非 panic
+        assert!(ImageSprite::try_create_circle(u32::MAX, 255, 0, 0, 255).is_err());
+        assert!(ImageSprite::try_create_circle(1 << 20, 255, 0, 0, 255).is_err());
+    }
+
+    #[test]
+    fn test_create_rounded_rectangle() {
+        let rect = ImageSprite::create_rounded_rectangle(20, 10, 4.0, 0, 0, 255, 255);
+        assert_eq!((rect.width(), rect.height()), (20, 10));
+
+        let alpha = |x: u32, y: u32| rect.buffer()[((y * 20 + x) * 4 + 3) as usize];
+        assert_eq!(alpha(0, 0), 0);
+        assert_eq!(alpha(10, 5), 255);
+        // 直边不受圆角影响
+        assert_eq!(alpha(10, 0), 255);
+        assert_eq!(alpha(0, 5), 255);
+        // 圆角弧线上的像素部分透明
+        assert!((1..255).contains(&alpha(1, 1)));
+
+        // 半径超过短边一半时钳制，两端成为半圆
+        let pill = ImageSprite::create_rounded_rectangle(20, 10, 100.0, 0, 0, 255, 255);
+        let clamped = ImageSprite::create_rounded_rectangle(20, 10, 5.0, 0, 0, 255, 255);
+        assert_eq!(pill.buffer(), clamped.buffer());
+
+        let huge = ImageSprite::try_create_rounded_rectangle(u32::MAX, u32::MAX, 4.0, 0, 0, 0, 255);
+        assert!(huge.is_err());
+    }
+
+    #[test]
+    fn test_bounding_box_grows_when_rotated() {
+        let mut sprite = ImageSprite::create_rectangle(10, 10, 0, 0, 0, 255);
+        sprite.set_position(50.0, 50.0);
+        let (min, max) = sprite.bounding_box();
+        assert!((min - Vec2::new(45.0, 45.0)).is_zero(1e-4));
+        assert!((max - Vec2::new(55.0, 55.0)).is_zero(1e-4));
+
+        // 旋转 45° 后包围盒边长变为 10 * sqrt(2)
+        sprite.set_rotation_degrees(45.0);
+        let (min, max) = sprite.bounding_box();
+        let half = 5.0 * std::f32::consts::SQRT_2;
+        assert!((min - Vec2::new(50.0 - half, 50.0 - half)).is_zero(1e-4));
+        assert!((max - Vec2::new(50.0 + half, 50.0 + half)).is_zero(1e-4));
+    }
+
+    #[test]
+    fn test_try_new_rejects_oversized_sprite() {
+        assert!(ImageSprite::try_new(32768, 32768, ImageFormat::Rgba).is_err());
+        let sprite = ImageSprite::try_new(0, 2, ImageFormat::Rgb).unwrap();
+        assert_eq!(sprite.buffer().len(), 6);
+    }
+
+    #[test]
+    fn test_try_from_buffer_rejects_oversized_sprite() {
+        // 0 被钳制为 1 后仍需 u32::MAX * 4 字节，不能在 u32 下回绕
+        assert!(ImageSprite::try_from_buffer(Vec::new(), 0, u32::MAX, ImageFormat::Rgba).is_err());
+        let sprite = ImageSprite::try_from_buffer(Vec::new(), 3, 0, ImageFormat::Rgb).unwrap();
+        assert_eq!((sprite.width(), sprite.height()), (3, 1));
+        assert_eq!(sprite.buffer().len(), 9);
+    }
+
+    #[test]
+    fn test_blend_modes_gray_over_gray() {
+        assert_eq!(composite_gray(BlendMode::Normal), 100);
+        assert_eq!(composite_gray(BlendMode::Additive), 255);
+        // 100 * 200 / 255 ≈ 78
+        assert_eq!(composite_gray(BlendMode::Multiply), 78);
+        // 255 - 155 * 55 / 255 ≈ 222
+        assert_eq!(composite_gray(BlendMode::Screen), 222);
+    }
+
+    #[test]
+    fn test_multiply_respects_alpha() {
+        let mut sprite = ImageSprite::create_rectangle(1, 1, 0, 0, 0, 255);
+        sprite
+            .set_anchor(0.0, 0.0)
+            .set_blend_mode(BlendMode::Multiply)
+            .set_opacity(0.5);
+        let mut target = vec![200, 200, 200, 255];
+        sprite.render_to(&mut target, 1, 1);
+        // 黑色正片叠底得 0，半透明时与原色各占一半
+        assert_eq!(target[0], 100);
+        assert_eq!(target[3], 255);
+    }
+
+    #[test]
+    fn test_source_rect_renders_atlas_frame() {
+        // 4x1 图集，两帧各 2x1
+        let mut sprite =
+            ImageSprite::from_buffer(vec![10, 20, 200, 210], 4, 1, ImageFormat::Grayscale);
+        sprite.set_anchor(0.0, 0.0).set_source_rect(2, 0, 2, 1);
+        assert_eq!((sprite.width(), sprite.height()), (2, 1));
+
+        let mut target = vec![0u8; 4 * 4];
+        sprite.render_to(&mut target, 4, 1);
+        assert_eq!(target[0], 200);
+        assert_eq!(target[4], 210);
+        assert_eq!(target[8], 0); // 只绘制一帧大小
+
+        sprite.clear_source_rect();
+        assert_eq!(sprite.width(), 4);
+        sprite.render_to(&mut target, 4, 1);
+        assert_eq!(target[0], 10);
+    }
+
+    #[test]
+    fn test_source_rect_clamped_to_image() {
+        let mut sprite = ImageSprite::new(4, 4, ImageFormat::Rgba);
+        sprite.set_source_rect(3, 9, 10, 0);
+        assert_eq!(sprite.source_rect(), Some((3, 3, 1, 1)));
+    }
+
+    #[test]
+    fn test_flip_samples_opposite_column() {
+        let mut sprite = ImageSprite::from_buffer(vec![10, 200], 2, 1, ImageFormat::Grayscale);
+        sprite.set_anchor(0.0, 0.0).set_flip_x(true);
+        let mut target = vec![0u8; 2 * 4];
+        sprite.render_to(&mut target, 2, 1);
+        assert_eq!(target[0], 200);
+        assert_eq!(target[4], 10);
+
+        let mut sprite = ImageSprite::from_buffer(vec![10, 200], 1, 2, ImageFormat::Grayscale);
+        sprite.set_anchor(0.0, 0.0).set_flip_y(true);
+        sprite.render_to(&mut target, 1, 2);
+        assert_eq!(target[0], 200);
+        assert_eq!(target[4], 10);
+    }
+
+    #[test]
+    fn test_mirror_edge_mode_right_edge() {
+        let mut sprite = ImageSprite::from_buffer(vec![0, 100, 200], 3, 1, ImageFormat::Grayscale);
+        sprite
+            .set_anchor(0.0, 0.0)
+            .set_scale(10.0, 1.0)
+            .set_sampling_mode(SamplingMode::Bilinear);
+        let mut target = vec![0u8; 30 * 4];
+
+        // 最右侧像素中心对应源坐标 2.95，插值邻居越界
+        sprite.render_to(&mut target, 30, 1);
+        assert_eq!(target[29 * 4], 200);
+
+        sprite.set_edge_mode(EdgeMode::Mirror);
+        sprite.render_to(&mut target, 30, 1);
+        assert_eq!(target[29 * 4], 155);
+    }
+
+    #[test]
+    fn test_tint_half_red() {
+        let mut sprite = ImageSprite::create_rectangle(1, 1, 255, 255, 255, 255);
+        sprite.set_anchor(0.0, 0.0).set_tint(1.0, 0.0, 0.0, 0.5);
+
+        // 黑色不透明背景上绘制半透明红色
+        let mut target = vec![0, 0, 0, 255];
+        sprite.render_to(&mut target, 1, 1);
+        assert_eq!(target, vec![128, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_opacity_scales_alpha() {
+        let mut sprite = ImageSprite::create_rectangle(2, 2, 200, 100, 0, 255);
+        sprite.set_anchor(0.0, 0.0).set_opacity(0.5);
+        let mut target = vec![0u8; 2 * 2 * 4];
+        sprite.render_to(&mut target, 2, 2);
+        assert_eq!(&target[..4], &[100, 50, 0, 128]);
+
+        // 超出范围的值被钳制，0 时不绘制
+        sprite.set_opacity(-1.0);
+        assert_eq!(sprite.opacity(), 0.0);
+        let mut target = vec![7u8; 2 * 2 * 4];
+        sprite.render_to(&mut target, 2, 2);
+        assert!(target.iter().all(|&v| v == 7));
+
+        sprite.set_opacity(3.0);
+        assert_eq!(sprite.opacity(), 1.0);
+    }
+
+    #[test]
+    fn test_pixels_row_major() {
+        let sprite = ImageSprite::from_buffer(vec![10, 20, 30, 40], 2, 2, ImageFormat::Grayscale);
+        let pixels: Vec<_> = sprite.pixels().collect();
+        assert_eq!(
+            pixels,
+            vec![
+                (0, 0, [10, 10, 10, 255]),
+                (1, 0, [20, 20, 20, 255]),
+                (0, 1, [30, 30, 30, 255]),
+                (1, 1, [40, 40, 40, 255]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_through_trait_object() {
+        let sprite: Box<dyn Sprite> = Box::new(ImageSprite::new(2, 2, ImageFormat::Grayscale));
+        assert_eq!(sprite.format(), ImageFormat::Grayscale);
+
+        // 未引入 Sprite trait 时仍可直接调用
+        mod without_trait {
+            use crate::core::format::ImageFormat;
+            use crate::scene::sprite::ImageSprite;
+
+            pub fn format_of(sprite: &ImageSprite) -> ImageFormat {
+                sprite.format()
+            }
+        }
+        let sprite = ImageSprite::new(2, 2, ImageFormat::Rgb);
+        assert_eq!(without_trait::format_of(&sprite), ImageFormat::Rgb);
+    }
+
+    #[test]
+    fn test_bilinear_sampling_interpolates() {
+        let mut sprite = ImageSprite::from_buffer(vec![0, 200], 2, 1, ImageFormat::Grayscale);
+        sprite.set_anchor(0.0, 0.0).set_uniform_scale(4.0);
+        let mut target = vec![0u8; 8 * 4 * 4];
+
+        sprite.render_to(&mut target, 8, 4);
+        assert_eq!(target[4 * 4], 200); // 最近邻：硬边
+
+        sprite.set_sampling_mode(SamplingMode::Bilinear);
+        target.fill(0);
+        sprite.render_to(&mut target, 8, 4);
+        assert_eq!(target[0], 0);
+        assert_eq!(target[3 * 4], 75);
+        assert_eq!(target[4 * 4], 125);
+        assert_eq!(target[7 * 4], 200);
+    }
+
+    #[test]
+    fn test_bilinear_rgb_clamps_at_edges() {
+        let mut sprite =
+            ImageSprite::from_buffer(vec![0, 0, 0, 90, 180, 30], 2, 1, ImageFormat::Rgb);
+        sprite
+            .set_anchor(0.0, 0.0)
+            .set_scale(4.0, 2.0)
+            .set_sampling_mode(SamplingMode::Bilinear);
+        let mut target = vec![0u8; 8 * 2 * 4];
+        sprite.render_to(&mut target, 8, 2);
+
+        // 边缘像素钳制，不与另一侧环绕混合
+        assert_eq!(&target[..4], &[0, 0, 0, 255]);
+        assert_eq!(&target[7 * 4..8 * 4], &[90, 180, 30, 255]);
+        // 中间为插值结果，第二行与第一行一致
+        assert_eq!(&target[4 * 4..5 * 4], &[56, 113, 19, 255]);
+        assert_eq!(&target[12 * 4..13 * 4], &[56, 113, 19, 255]);
+    }
+
+    #[test]
+    fn test_transformed_corners_rotated_90() {
+        let mut sprite = ImageSprite::create_rectangle(4, 2, 255, 255, 255, 255);
+        sprite
+            .set_anchor(0.0, 0.0)
+            .set_position(10.0, 10.0)
+            .set_rotation_degrees(90.0);
+
+        let corners = sprite.transformed_corners();
+        let expected = [
+            Vec2::new(10.0, 10.0),
+            Vec2::new(10.0, 14.0),
+            Vec2::new(8.0, 14.0),
+            Vec2::new(8.0, 10.0),
+        ];
+        for (corner, expected) in corners.iter().zip(expected.iter()) {
+            assert!((corner.x - expected.x).abs() < 1e-4, "{corner:?}");
+            assert!((corner.y - expected.y).abs() < 1e-4, "{corner:?}");
+        }
+    }
+
     #[test]
     fn test_create_sprite() {
         let sprite = ImageSprite::new(100, 100, ImageFormat::Rgba);
@@ -382,4 +1634,299 @@ mod tests {
         sprite.set_z_order(5);
         assert_eq!(sprite.z_order(), 5);
     }
+
+    #[test]
+    fn test_update_buffer() {
+        let mut sprite = ImageSprite::new(2, 1, ImageFormat::Rgb);
+        assert!(sprite.update_buffer(&[1, 2, 3, 4, 5, 6]).is_ok());
+        assert_eq!(sprite.buffer(), &[1, 2, 3, 4, 5, 6]);
+
+        // 长度不符时拒绝并保持原数据
+        assert!(sprite.update_buffer(&[0; 4]).is_err());
+        assert_eq!(sprite.buffer(), &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_to_rgba_buffer_from_grayscale() {
+        let sprite = ImageSprite::from_buffer(vec![0, 64, 128, 255], 2, 2, ImageFormat::Grayscale);
+        let rgba = sprite.to_rgba_buffer();
+
+        assert_eq!(rgba.len(), 2 * 2 * 4);
+        for (pixel, &g) in rgba.chunks(4).zip(sprite.buffer().iter()) {
+            assert_eq!(pixel, &[g, g, g, 255]);
+        }
+    }
+
+    #[test]
+    fn test_zero_dimensions_clamped() {
+        let sprite = ImageSprite::new(0, 10, ImageFormat::Rgba);
+        assert_eq!(sprite.width(), 1);
+        assert_eq!(sprite.height(), 10);
+        assert_eq!(sprite.buffer().len(), 10 * 4);
+
+        let sprite = ImageSprite::from_buffer(Vec::new(), 0, 0, ImageFormat::Rgb);
+        assert_eq!(sprite.width(), 1);
+        assert_eq!(sprite.height(), 1);
+        assert_eq!(sprite.buffer().len(), 3);
+
+        // 渲染不应 panic
+        let mut sprite = ImageSprite::create_rectangle(0, 0, 255, 255, 255, 255);
+        let mut target = vec![0u8; 4 * 4 * 4];
+        sprite.render_to(&mut target, 4, 4);
+    }
+
+    #[test]
+    fn test_color_key_magenta() {
+        // 2x1 RGB 图像：洋红背景 + 绿色前景
+        let buffer = vec![255, 0, 255, 0, 200, 0];
+        let sprite =
+            ImageSprite::from_buffer(buffer, 2, 1, ImageFormat::Rgb).with_color_key(0xFF00FFFF, 0);
+
+        assert_eq!(sprite.format(), ImageFormat::Rgba);
+        assert_eq!(sprite.buffer(), &[255, 0, 255, 0, 0, 200, 0, 255]);
+    }
+
+    #[test]
+    fn test_color_key_tolerance() {
+        let buffer = vec![250, 5, 250, 255, 240, 20, 240, 255];
+        let sprite = ImageSprite::from_buffer(buffer, 2, 1, ImageFormat::Rgba)
+            .with_color_key(0xFF00FFFF, 10);
+
+        // 第一个像素在容差内被键出，第二个超出容差保持不透明
+        assert_eq!(sprite.buffer()[3], 0);
+        assert_eq!(sprite.buffer()[7], 255);
+    }
+
+    #[test]
+    fn test_scan_region_limited_to_clip() {
+        let mut sprite = ImageSprite::create_rectangle(10, 10, 255, 255, 255, 255);
+        sprite.set_anchor(0.0, 0.0).set_rotation_degrees(30.0);
+        sprite.set_clip_rect(Some((2, 2, 3, 3)));
+
+        let matrix = sprite.get_transform_matrix();
+        let region = sprite.scan_region(&matrix, 20, 20).unwrap();
+
+        // 扫描像素数即裁剪区域面积
+        let (x0, y0, x1, y1) = region;
+        assert_eq!(region, (2, 2, 5, 5));
+        assert_eq!((x1 - x0) * (y1 - y0), 9);
+    }
+
+    #[test]
+    fn test_scan_region_partly_off_screen() {
+        let mut sprite = ImageSprite::create_rectangle(6, 6, 255, 255, 255, 255);
+        sprite.set_anchor(0.0, 0.0).set_position(-3.0, 17.0);
+
+        let matrix = sprite.get_transform_matrix();
+        // 包围盒 [-3, 3) x [17, 23)，钳制到 20x20 的目标
+        assert_eq!(sprite.scan_region(&matrix, 20, 20), Some((0, 17, 4, 20)));
+
+        let mut target = vec![0u8; 20 * 20 * 4];
+        sprite.render_to(&mut target, 20, 20);
+        let at = |x: usize, y: usize| target[(y * 20 + x) * 4];
+        assert_eq!(at(0, 17), 255);
+        assert_eq!(at(2, 19), 255);
+        assert_eq!(at(3, 17), 0);
+        assert_eq!(at(0, 16), 0);
+    }
+
+    #[test]
+    fn test_scan_region_fully_off_screen_is_empty() {
+        let mut sprite = ImageSprite::create_rectangle(4, 4, 255, 255, 255, 255);
+        sprite.set_anchor(0.0, 0.0).set_position(-50.0, 5.0);
+
+        let matrix = sprite.get_transform_matrix();
+        assert!(sprite.scan_region(&matrix, 20, 20).is_none());
+    }
+
+    #[test]
+    fn test_clip_rect_limits_drawing() {
+        let mut sprite = ImageSprite::create_rectangle(4, 4, 255, 255, 255, 255);
+        sprite.set_anchor(0.0, 0.0);
+        sprite.set_clip_rect(Some((0, 0, 2, 4)));
+
+        let mut target = vec![0u8; 4 * 4 * 4];
+        sprite.render_to(&mut target, 4, 4);
+
+        // 左半部分绘制，右半部分保持不变
+        assert_eq!(target[0], 255);
+        assert_eq!(target[4], 255);
+        assert_eq!(target[8], 0);
+        assert_eq!(target[12], 0);
+    }
+
+    #[test]
+    fn test_scan_region_outside_clip_is_empty() {
+        let mut sprite = ImageSprite::create_rectangle(4, 4, 255, 255, 255, 255);
+        sprite.set_anchor(0.0, 0.0).set_position(10.0, 10.0);
+        sprite.set_clip_rect(Some((0, 0, 5, 5)));
+
+        let matrix = sprite.get_transform_matrix();
+        assert!(sprite.scan_region(&matrix, 20, 20).is_none());
+    }
+
+    #[test]
+    fn test_render_to_rejects_short_target() {
+        let mut sprite = ImageSprite::create_rectangle(4, 4, 255, 255, 255, 255);
+        sprite.set_anchor(0.0, 0.0);
+
+        // 4x4 RGBA 需要 64 字节，这里只给 60 字节
+        let mut target = vec![0u8; 60];
+        sprite.render_to(&mut target, 4, 4);
+        assert!(target.iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    fn test_reset_render_state() {
+        let mut sprite = ImageSprite::create_rectangle(4, 4, 1, 2, 3, 255);
+        sprite.set_position(5.0, 6.0);
+        sprite
+            .set_blend_mode(BlendMode::Additive)
+            .set_clip_rect(Some((0, 0, 2, 2)));
+
+        sprite.set_sampling_mode(SamplingMode::Bilinear);
+        sprite.set_opacity(0.5).set_tint(1.0, 0.0, 0.0, 1.0);
+        sprite.set_edge_mode(EdgeMode::Mirror);
+        sprite.set_aa_mode(AaMode::Msaa4x);
+        sprite.set_premultiplied(true);
+        sprite.reset_render_state();
+        assert_eq!(sprite.aa_mode(), AaMode::None);
+        assert!(!sprite.premultiplied());
+        assert_eq!(sprite.edge_mode(), EdgeMode::Clamp);
+        assert_eq!(sprite.opacity(), 1.0);
+        assert_eq!(sprite.tint(), [1.0; 4]);
+        assert_eq!(sprite.blend_mode(), BlendMode::Normal);
+        assert_eq!(sprite.clip_rect(), None);
+        assert_eq!(sprite.sampling_mode(), SamplingMode::Nearest);
+
+        // 变换和像素数据保持不变
+        assert!((sprite.transform().position.x - 5.0).abs() < 1e-6);
+        assert_eq!(&sprite.buffer()[0..4], &[1, 2, 3, 255]);
+    }
+
+    #[test]
+    fn test_additive_blend_saturates() {
+        let mut dst = [200u8, 100, 0, 255];
+        blend_pixel(&mut dst, [100, 100, 100, 255], BlendMode::Additive);
+        assert_eq!(dst, [255, 200, 100, 255]);
+    }
+
+    #[test]
+    fn test_aa_mode_smooths_rotated_edges() {
+        let render = |mode: AaMode| {
+            let mut sprite = ImageSprite::create_rectangle(10, 10, 255, 255, 255, 255);
+            sprite.set_aa_mode(mode);
+            sprite.transform_mut().set_position(10.0, 10.0);
+            sprite.transform_mut().set_rotation_degrees(45.0);
+            let mut target = vec![0u8; 20 * 20 * 4];
+            sprite.render_to(&mut target, 20, 20);
+            target
+        };
+        let partial = |target: &[u8]| {
+            target
+                .chunks_exact(4)
+                .filter(|p| p[3] > 0 && p[3] < 255)
+                .count()
+        };
+
+        // 不抗锯齿时边缘只有完全透明或完全不透明
+        assert_eq!(partial(&render(AaMode::None)), 0);
+        assert!(partial(&render(AaMode::Msaa2x)) > 0);
+        let smooth = render(AaMode::Msaa4x);
+        assert!(partial(&smooth) > 0);
+        // 中心仍完全不透明
+        let center = (10 * 20 + 10) * 4;
+        assert_eq!(&smooth[center..center + 4], &[255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_premultiplied_blending() {
+        let composite = |pixel: [u8; 4], premultiplied: bool| {
+            let mut sprite = ImageSprite::from_buffer(pixel.to_vec(), 1, 1, ImageFormat::Rgba);
+            sprite.set_anchor(0.0, 0.0).set_premultiplied(premultiplied);
+            let mut target = vec![0, 255, 0, 255];
+            sprite.render_to(&mut target, 1, 1);
+            target
+        };
+
+        // 半透明红色：非预乘 (255, 0, 0, 128) 与预乘 (128, 0, 0, 128) 结果一致
+        let straight = composite([255, 0, 0, 128], false);
+        let premultiplied = composite([128, 0, 0, 128], true);
+        for (s, p) in straight.iter().zip(&premultiplied) {
+            assert!(s.abs_diff(*p) <= 1, "{straight:?} vs {premultiplied:?}");
+        }
+        assert_eq!(premultiplied, [128, 127, 0, 255]);
+
+        // 同一数据按预乘解释时颜色直接相加，不再乘以 alpha
+        assert_eq!(composite([255, 0, 0, 128], true), [255, 127, 0, 255]);
+    }
+
+    #[test]
+    fn test_opacity_and_tint_through_trait_object() {
+        let mut sprite: Box<dyn Sprite> =
+            Box::new(ImageSprite::create_rectangle(1, 1, 200, 100, 50, 255));
+        assert_eq!(sprite.opacity(), 1.0);
+        assert_eq!(sprite.tint(), [1.0; 4]);
+
+        sprite.set_opacity(2.0);
+        assert_eq!(sprite.opacity(), 1.0);
+        sprite.set_opacity(0.5);
+        sprite.set_tint(1.0, 0.5, 0.0, 1.0);
+        assert_eq!(sprite.opacity(), 0.5);
+        assert_eq!(sprite.tint(), [1.0, 0.5, 0.0, 1.0]);
+
+        // 通过 trait 设置的值参与渲染
+        sprite.transform_mut().set_anchor(0.0, 0.0);
+        let mut target = vec![0, 0, 0, 255];
+        sprite.render_to(&mut target, 1, 1);
+        assert_eq!(target[1], 25);
+        assert_eq!(target[2], 0);
+    }
+
+    #[test]
+    fn test_render_with_matrix_defaults_to_render_to() {
+        // 只实现必需方法的外部精灵
+        struct Dot {
+            transform: Transform2D,
+        }
+
+        impl Sprite for Dot {
+            fn width(&self) -> u32 {
+                1
+            }
+
+            fn height(&self) -> u32 {
+                1
+            }
+
+            fn z_order(&self) -> i32 {
+                0
+            }
+
+            fn set_z_order(&mut self, _z: i32) {}
+
+            fn transform(&self) -> &Transform2D {
+                &self.transform
+            }
+
+            fn transform_mut(&mut self) -> &mut Transform2D {
+                &mut self.transform
+            }
+
+            fn render_to(&mut self, target: &mut [u8], _target_width: u32, _target_height: u32) {
+                target[..4].copy_from_slice(&[1, 2, 3, 255]);
+            }
+
+            fn id(&self) -> u64 {
+                0
+            }
+        }
+
+        let mut dot = Dot {
+            transform: Transform2D::new(),
+        };
+        let mut target = vec![0; 8];
+        dot.render_with_matrix(&Matrix3x3::identity(), &mut target, 2, 1);
+        assert_eq!(target, [1, 2, 3, 255, 0, 0, 0, 0]);
+    }
 }
@@ -4,7 +4,10 @@
 
 use wasm_bindgen::prelude::*;
 
-use crate::scene::{ImageSprite, Scene};
+use crate::core::color::unpack_rgba;
+use crate::core::{ImageFormat, SamplingMode};
+use crate::math::Vec2;
+use crate::scene::{ImageSprite, Scene, Sprite};
 
 /// WASM Scene 包装器
 #[wasm_bindgen]
@@ -12,6 +15,8 @@ pub struct WasmScene {
     scene: Scene,
     /// 保存精灵的 ID 列表（用于访问）
     sprite_ids: Vec<u64>,
+    /// 最近一次 render 的耗时（微秒）
+    last_render_micros: f64,
 }
 
 #[wasm_bindgen]
@@ -22,6 +27,7 @@ impl WasmScene {
         WasmScene {
             scene: Scene::new(width, height),
             sprite_ids: Vec::new(),
+            last_render_micros: 0.0,
         }
     }
 
@@ -40,16 +46,31 @@ impl WasmScene {
         self.scene.ptr()
     }
 
-    /// 获取 buffer 长度
+    /// 获取 buffer 长度（字节数，与精灵数量无关）
     pub fn len(&self) -> usize {
         self.scene.len()
     }
 
+    /// 检查场景是否没有精灵（等价于 `sprite_count() == 0`，而非 `len() == 0`）
+    pub fn is_empty(&self) -> bool {
+        self.sprite_count() == 0
+    }
+
+    /// 获取 buffer 的哈希值，用于判断帧是否变化
+    pub fn buffer_hash(&self) -> u64 {
+        self.scene.buffer_hash()
+    }
+
     /// 设置背景颜色
     pub fn set_background_color(&mut self, r: u8, g: u8, b: u8, a: u8) {
         self.scene.set_background_color(r, g, b, a);
     }
 
+    /// 获取背景颜色（十六进制 0xRRGGBBAA）
+    pub fn get_background(&self) -> u32 {
+        self.scene.background_color()
+    }
+
     /// 添加长方形精灵
     ///
     /// 返回精灵索引（用于后续操作）
@@ -60,104 +81,209 @@ impl WasmScene {
         self.sprite_ids.len() - 1
     }
 
-    /// 设置精灵位置
-    pub fn set_sprite_position(&mut self, index: usize, x: f32, y: f32) {
-        if index < self.sprite_ids.len() {
-            let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
-                sprite.transform_mut().set_position(x, y);
-            }
-        }
+    /// 添加圆形精灵（边缘抗锯齿，尺寸为 `2 * radius`）
+    ///
+    /// 返回精灵索引（用于后续操作），半径过大时返回错误
+    pub fn add_circle(&mut self, radius: u32, r: u8, g: u8, b: u8, a: u8) -> Result<usize, String> {
+        let sprite = ImageSprite::try_create_circle(radius, r, g, b, a)?;
+        let id = self.scene.add(sprite);
+        self.sprite_ids.push(id);
+        Ok(self.sprite_ids.len() - 1)
     }
 
-    /// 设置精灵旋转（角度）
-    pub fn set_sprite_rotation(&mut self, index: usize, degrees: f32) {
-        if index < self.sprite_ids.len() {
-            let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
-                sprite.transform_mut().set_rotation_degrees(degrees);
-            }
-        }
+    /// 添加圆角矩形精灵（圆角边缘抗锯齿，半径超过短边一半时钳制）
+    ///
+    /// 返回精灵索引（用于后续操作），尺寸过大时返回错误
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_rounded_rectangle(
+        &mut self,
+        width: u32,
+        height: u32,
+        corner_radius: f32,
+        r: u8,
+        g: u8,
+        b: u8,
+        a: u8,
+    ) -> Result<usize, String> {
+        let sprite =
+            ImageSprite::try_create_rounded_rectangle(width, height, corner_radius, r, g, b, a)?;
+        let id = self.scene.add(sprite);
+        self.sprite_ids.push(id);
+        Ok(self.sprite_ids.len() - 1)
     }
 
-    /// 设置精灵缩放
-    pub fn set_sprite_scale(&mut self, index: usize, sx: f32, sy: f32) {
-        if index < self.sprite_ids.len() {
-            let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
-                sprite.transform_mut().set_scale(sx, sy);
-            }
+    /// 批量添加 `count` 个同色长方形精灵（颜色为 0xRRGGBBAA）
+    ///
+    /// 只填充一次像素数据，其余精灵直接复制，适合构建网格、棋盘等。
+    /// 返回第一个精灵的索引，后续精灵索引依次递增
+    pub fn add_rectangles(&mut self, count: usize, width: u32, height: u32, color: u32) -> usize {
+        let start = self.sprite_ids.len();
+        if count == 0 {
+            return start;
         }
-    }
 
-    /// 设置精灵均匀缩放
-    pub fn set_sprite_uniform_scale(&mut self, index: usize, scale: f32) {
-        if index < self.sprite_ids.len() {
-            let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
-                sprite.transform_mut().set_uniform_scale(scale);
-            }
+        let (r, g, b, a) = unpack_rgba(color);
+        let first = ImageSprite::create_rectangle(width, height, r, g, b, a);
+        let (width, height) = (first.width(), first.height());
+        let pixels = first.buffer().to_vec();
+
+        self.sprite_ids.push(self.scene.add(first));
+        for _ in 1..count {
+            let sprite = ImageSprite::from_buffer(pixels.clone(), width, height, ImageFormat::Rgba);
+            self.sprite_ids.push(self.scene.add(sprite));
         }
+        start
     }
 
-    /// 设置精灵锚点
-    pub fn set_sprite_anchor(&mut self, index: usize, ax: f32, ay: f32) {
-        if index < self.sprite_ids.len() {
-            let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
-                sprite.transform_mut().set_anchor(ax, ay);
+    /// 从 PNG/JPEG 编码数据添加图像精灵
+    ///
+    /// 返回精灵索引，解码失败返回 -1
+    pub fn add_image(&mut self, bytes: &[u8]) -> i32 {
+        match ImageSprite::from_encoded(bytes) {
+            Ok(sprite) => {
+                let id = self.scene.add(sprite);
+                self.sprite_ids.push(id);
+                (self.sprite_ids.len() - 1) as i32
             }
+            Err(_) => -1,
         }
     }
 
-    /// 平移精灵
-    pub fn translate_sprite(&mut self, index: usize, dx: f32, dy: f32) {
+    /// 替换精灵的像素数据
+    ///
+    /// 数据长度必须与精灵尺寸和格式一致，成功返回 true
+    pub fn update_sprite_buffer(&mut self, index: usize, data: &[u8]) -> bool {
         if index < self.sprite_ids.len() {
             let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
-                sprite.transform_mut().translate(dx, dy);
+            if let Some(sprite) = self
+                .scene
+                .get_sprite_mut(id)
+                .and_then(|s| s.as_image_sprite_mut())
+            {
+                return sprite.update_buffer(data).is_ok();
             }
         }
+        false
     }
 
-    /// 旋转精灵（增量，角度）
-    pub fn rotate_sprite(&mut self, index: usize, degrees: f32) {
-        if index < self.sprite_ids.len() {
-            let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
-                sprite.transform_mut().rotate_degrees(degrees);
-            }
-        }
+    /// 设置精灵位置，索引无效时返回 false
+    pub fn set_sprite_position(&mut self, index: usize, x: f32, y: f32) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().set_position(x, y);
+        })
     }
 
-    /// 缩放精灵（乘法）
-    pub fn scale_sprite_by(&mut self, index: usize, sx: f32, sy: f32) {
-        if index < self.sprite_ids.len() {
-            let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
-                sprite.transform_mut().scale_by(sx, sy);
-            }
-        }
+    /// 一次性设置精灵的位置、旋转（角度）和缩放，索引无效时返回 false
+    ///
+    /// 动画每帧更新大量精灵时使用，比分别调用各个 setter 少几次跨边界调用和矩阵失效
+    pub fn set_sprite_transform(
+        &mut self,
+        index: usize,
+        x: f32,
+        y: f32,
+        rotation_degrees: f32,
+        sx: f32,
+        sy: f32,
+    ) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().set_trs(
+                Vec2::new(x, y),
+                rotation_degrees.to_radians(),
+                Vec2::new(sx, sy),
+            );
+        })
     }
 
-    /// 重置精灵变换
-    pub fn reset_sprite_transform(&mut self, index: usize) {
-        if index < self.sprite_ids.len() {
-            let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
-                let transform = sprite.transform_mut();
-                transform.set_position(0.0, 0.0);
-                transform.set_rotation(0.0);
-                transform.set_scale(1.0, 1.0);
-            }
-        }
+    /// 设置精灵旋转（角度），索引无效时返回 false
+    pub fn set_sprite_rotation(&mut self, index: usize, degrees: f32) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().set_rotation_degrees(degrees);
+        })
+    }
+
+    /// 设置精灵旋转（角度），按 `step` 吸附到最近的倍数，索引无效时返回 false
+    pub fn set_sprite_rotation_snapped(&mut self, index: usize, degrees: f32, step: f32) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().set_rotation_snapped(degrees, step);
+        })
+    }
+
+    /// 设置精灵缩放，索引无效时返回 false
+    pub fn set_sprite_scale(&mut self, index: usize, sx: f32, sy: f32) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().set_scale(sx, sy);
+        })
+    }
+
+    /// 设置精灵均匀缩放，索引无效时返回 false
+    pub fn set_sprite_uniform_scale(&mut self, index: usize, scale: f32) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().set_uniform_scale(scale);
+        })
+    }
+
+    /// 设置精灵锚点，索引无效时返回 false
+    pub fn set_sprite_anchor(&mut self, index: usize, ax: f32, ay: f32) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().set_anchor(ax, ay);
+        })
+    }
+
+    /// 设置精灵不透明度（0.0-1.0，超出范围会被钳制），索引无效时返回 false
+    pub fn set_sprite_opacity(&mut self, index: usize, opacity: f32) -> bool {
+        self.with_image_sprite_mut(index, |sprite| {
+            sprite.set_opacity(opacity);
+        })
+    }
+
+    /// 设置精灵采样模式（0 = 最近邻，1 = 双线性），索引或模式无效时返回 false
+    pub fn set_sprite_sampling(&mut self, index: usize, mode: u32) -> bool {
+        let mode = match mode {
+            0 => SamplingMode::Nearest,
+            1 => SamplingMode::Bilinear,
+            _ => return false,
+        };
+        self.with_image_sprite_mut(index, |sprite| {
+            sprite.set_sampling_mode(mode);
+        })
+    }
+
+    /// 平移精灵，索引无效时返回 false
+    pub fn translate_sprite(&mut self, index: usize, dx: f32, dy: f32) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().translate(dx, dy);
+        })
+    }
+
+    /// 旋转精灵（增量，角度），索引无效时返回 false
+    pub fn rotate_sprite(&mut self, index: usize, degrees: f32) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().rotate_degrees(degrees);
+        })
+    }
+
+    /// 缩放精灵（乘法），索引无效时返回 false
+    pub fn scale_sprite_by(&mut self, index: usize, sx: f32, sy: f32) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            sprite.transform_mut().scale_by(sx, sy);
+        })
+    }
+
+    /// 重置精灵变换，索引无效时返回 false
+    pub fn reset_sprite_transform(&mut self, index: usize) -> bool {
+        self.with_sprite_mut(index, |sprite| {
+            let transform = sprite.transform_mut();
+            transform.set_position(0.0, 0.0);
+            transform.set_rotation(0.0);
+            transform.set_scale(1.0, 1.0);
+        })
     }
 
     /// 获取精灵位置 X
     pub fn get_sprite_position_x(&mut self, index: usize) -> f32 {
         if index < self.sprite_ids.len() {
             let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
+            if let Some(sprite) = self.scene.get_sprite(id) {
                 return sprite.transform().position.x;
             }
         }
@@ -168,7 +294,7 @@ impl WasmScene {
     pub fn get_sprite_position_y(&mut self, index: usize) -> f32 {
         if index < self.sprite_ids.len() {
             let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
+            if let Some(sprite) = self.scene.get_sprite(id) {
                 return sprite.transform().position.y;
             }
         }
@@ -179,7 +305,7 @@ impl WasmScene {
     pub fn get_sprite_rotation(&mut self, index: usize) -> f32 {
         if index < self.sprite_ids.len() {
             let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
+            if let Some(sprite) = self.scene.get_sprite(id) {
                 return sprite.transform().rotation;
             }
         }
@@ -190,7 +316,7 @@ impl WasmScene {
     pub fn get_sprite_scale_x(&mut self, index: usize) -> f32 {
         if index < self.sprite_ids.len() {
             let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
+            if let Some(sprite) = self.scene.get_sprite(id) {
                 return sprite.transform().scale.x;
             }
         }
@@ -201,16 +327,98 @@ impl WasmScene {
     pub fn get_sprite_scale_y(&mut self, index: usize) -> f32 {
         if index < self.sprite_ids.len() {
             let id = self.sprite_ids[index];
-            if let Some(sprite) = self.scene.get_sprite_mut(id) {
+            if let Some(sprite) = self.scene.get_sprite(id) {
                 return sprite.transform().scale.y;
             }
         }
         1.0
     }
 
+    /// 一次获取精灵完整变换，返回 [x, y, rotation, sx, sy, anchor_x, anchor_y]
+    ///
+    /// 旋转为弧度，与 `get_sprite_rotation` 一致；索引无效时返回空数组
+    pub fn get_sprite_transform(&self, index: usize) -> Box<[f32]> {
+        let Some(sprite) = self
+            .sprite_ids
+            .get(index)
+            .and_then(|&id| self.scene.get_sprite(id))
+        else {
+            return Box::new([]);
+        };
+        let t = sprite.transform();
+        Box::new([
+            t.position.x,
+            t.position.y,
+            t.rotation,
+            t.scale.x,
+            t.scale.y,
+            t.anchor.x,
+            t.anchor.y,
+        ])
+    }
+
+    /// 获取精灵四个角点的屏幕坐标，返回 [x0, y0, x1, y1, x2, y2, x3, y3]
+    ///
+    /// 包含父级和相机变换，不会触发重新渲染；索引无效时返回空数组
+    pub fn get_sprite_corners(&mut self, index: usize) -> Vec<f32> {
+        self.sprite_ids
+            .get(index)
+            .and_then(|&id| self.scene.screen_corners(id))
+            .map(|corners| {
+                corners
+                    .iter()
+                    .flat_map(|corner| [corner.x, corner.y])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 判断精灵是否出现在屏幕上（考虑相机）
+    pub fn is_sprite_on_screen(&mut self, index: usize) -> bool {
+        if index < self.sprite_ids.len() {
+            let id = self.sprite_ids[index];
+            return self.scene.is_sprite_visible_on_screen(id);
+        }
+        false
+    }
+
+    /// 设置输出 buffer 是否使用预乘 alpha
+    ///
+    /// 默认非预乘，与 `putImageData` 一致；上传为 WebGL 预乘纹理时可开启
+    pub fn set_output_premultiplied(&mut self, premultiplied: bool) {
+        self.scene.set_output_premultiplied(premultiplied);
+    }
+
+    /// 将最近一次渲染结果编码为 PNG，可直接用于下载
+    ///
+    /// 需先调用 `render`
+    pub fn to_png(&self) -> Box<[u8]> {
+        self.scene.to_png().into_boxed_slice()
+    }
+
     /// 渲染场景
     pub fn render(&mut self) {
+        let start = now_micros();
         self.scene.render();
+        self.last_render_micros = (now_micros() - start).max(0.0);
+    }
+
+    /// 最近一次 render 的耗时（微秒），可用于显示帧时间
+    pub fn last_render_micros(&self) -> f64 {
+        self.last_render_micros
+    }
+
+    /// 删除指定索引的精灵，索引无效时返回 false
+    ///
+    /// 之后的精灵索引依次前移一位，JS 端持有的旧索引会失效
+    pub fn remove_sprite(&mut self, index: u32) -> bool {
+        let index = index as usize;
+        if index < self.sprite_ids.len() {
+            let id = self.sprite_ids.remove(index);
+            self.scene.remove(id)
+        } else {
+            false
+        }
     }
 
     /// 清空场景
@@ -219,8 +427,463 @@ impl WasmScene {
         self.sprite_ids.clear();
     }
 
-    /// 获取精灵数量
+    /// 获取精灵数量（实际参与渲染的精灵）
     pub fn sprite_count(&self) -> usize {
         self.scene.sprite_count()
     }
+
+    /// 设置相机位置（屏幕左上角对应的世界坐标）
+    pub fn set_camera_position(&mut self, x: f32, y: f32) {
+        self.scene.set_camera_position(x, y);
+    }
+
+    /// 设置相机缩放（非正值会被忽略）
+    pub fn set_camera_zoom(&mut self, zoom: f32) {
+        self.scene.set_camera_zoom(zoom);
+    }
+
+    /// 平移相机（世界坐标增量）
+    pub fn pan_camera(&mut self, dx: f32, dy: f32) {
+        self.scene.pan(dx, dy);
+    }
+
+    /// 缩放相机（乘法）
+    pub fn zoom_camera(&mut self, factor: f32) {
+        self.scene.zoom(factor);
+    }
+
+    /// 获取相机位置 X
+    pub fn camera_x(&self) -> f32 {
+        self.scene.camera_position().x
+    }
+
+    /// 获取相机位置 Y
+    pub fn camera_y(&self) -> f32 {
+        self.scene.camera_position().y
+    }
+
+    /// 获取相机缩放
+    pub fn camera_zoom(&self) -> f32 {
+        self.scene.camera_zoom()
+    }
+
+    /// 拾取屏幕坐标处最上层的精灵，返回其索引，未命中时返回 -1
+    pub fn pick(&mut self, x: f32, y: f32) -> i64 {
+        self.scene
+            .pick(x, y)
+            .and_then(|id| self.sprite_ids.iter().position(|&i| i == id))
+            .map_or(-1, |index| index as i64)
+    }
+
+    /// 获取所有精灵 ID（按索引顺序，返回 Uint32Array）
+    ///
+    /// 索引在删除精灵后可能变化，JS 端可用 ID 维持稳定引用。
+    /// ID 按顺序递增分配，截断为 u32 以便在 JS 中使用
+    pub fn sprite_ids(&self) -> Vec<u32> {
+        self.sprite_ids.iter().map(|&id| id as u32).collect()
+    }
+}
+
+impl WasmScene {
+    /// 按索引修改精灵，索引无效时返回 false
+    fn with_sprite_mut(&mut self, index: usize, f: impl FnOnce(&mut Box<dyn Sprite>)) -> bool {
+        let Some(&id) = self.sprite_ids.get(index) else {
+            return false;
+        };
+        match self.scene.get_sprite_mut(id) {
+            Some(sprite) => {
+                f(sprite);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 按索引修改图像精灵，索引无效或不是图像精灵时返回 false
+    fn with_image_sprite_mut(&mut self, index: usize, f: impl FnOnce(&mut ImageSprite)) -> bool {
+        let Some(&id) = self.sprite_ids.get(index) else {
+            return false;
+        };
+        match self
+            .scene
+            .get_sprite_mut(id)
+            .and_then(|s| s.as_image_sprite_mut())
+        {
+            Some(sprite) => {
+                f(sprite);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 当前时间戳（微秒），只用于计算时间差
+#[cfg(target_arch = "wasm32")]
+fn now_micros() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now() * 1000.0)
+        .unwrap_or(0.0)
+}
+
+/// 当前时间戳（微秒），只用于计算时间差
+#[cfg(not(target_arch = "wasm32"))]
+fn now_micros() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_secs_f64() * 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_sprite_sampling_bilinear() {
+        let mut scene = WasmScene::new(8, 4);
+        scene.add_rectangle(2, 1, 0, 0, 0, 255);
+        assert!(scene.update_sprite_buffer(0, &[0, 0, 0, 255, 200, 200, 200, 255]));
+        scene.set_sprite_anchor(0, 0.0, 0.0);
+        scene.set_sprite_uniform_scale(0, 4.0);
+
+        // 未知模式被忽略，仍为最近邻
+        scene.set_sprite_sampling(0, 7);
+        scene.render();
+        assert_eq!(scene.scene.buffer()[4 * 4], 200);
+
+        scene.set_sprite_sampling(0, 1);
+        scene.render();
+        let value = scene.scene.buffer()[4 * 4];
+        assert!(value > 0 && value < 200, "{value}");
+    }
+
+    #[test]
+    fn test_last_render_micros_updates() {
+        let mut scene = WasmScene::new(64, 64);
+        assert_eq!(scene.last_render_micros(), 0.0);
+
+        for _ in 0..50 {
+            scene.add_rectangle(32, 32, 255, 0, 0, 255);
+        }
+        scene.render();
+        assert!(scene.last_render_micros() > 0.0);
+
+        scene.clear();
+        scene.render();
+        assert!(scene.last_render_micros() >= 0.0);
+    }
+
+    #[test]
+    fn test_set_sprite_opacity() {
+        let mut scene = WasmScene::new(2, 2);
+        scene.set_background_color(0, 0, 0, 255);
+        scene.add_rectangle(2, 2, 255, 255, 255, 255);
+        scene.set_sprite_anchor(0, 0.0, 0.0);
+
+        scene.set_sprite_opacity(0, 0.0);
+        scene.render();
+        assert_eq!(scene.scene.buffer()[0], 0);
+
+        scene.set_sprite_opacity(0, 0.5);
+        scene.render();
+        assert_eq!(scene.scene.buffer()[0], 128);
+    }
+
+    #[test]
+    fn test_output_premultiplied() {
+        let mut scene = WasmScene::new(1, 1);
+        scene.set_background_color(200, 100, 50, 128);
+        scene.render();
+        assert_eq!(scene.scene.buffer(), &[200, 100, 50, 128]);
+
+        scene.set_output_premultiplied(true);
+        scene.render();
+        assert_eq!(scene.scene.buffer(), &[100, 50, 25, 128]);
+    }
+
+    #[test]
+    fn test_add_rectangles_batch() {
+        let mut scene = WasmScene::new(100, 10);
+        scene.add_rectangle(1, 1, 0, 0, 0, 255);
+
+        let start = scene.add_rectangles(50, 2, 2, 0x11223344);
+        assert_eq!(start, 1);
+        assert_eq!(scene.sprite_count(), 51);
+
+        for index in start..start + 50 {
+            let id = scene.sprite_ids[index];
+            let sprite = scene
+                .scene
+                .get_sprite_mut(id)
+                .and_then(|s| s.as_image_sprite_mut())
+                .unwrap();
+            assert_eq!((sprite.width(), sprite.height()), (2, 2));
+            assert_eq!(&sprite.buffer()[12..16], &[0x11, 0x22, 0x33, 0x44]);
+        }
+        assert_eq!(scene.add_rectangles(0, 2, 2, 0), 51);
+    }
+
+    #[test]
+    fn test_add_image() {
+        let mut png = WasmScene::new(2, 2);
+        png.set_background_color(1, 2, 3, 255);
+        png.render();
+        let bytes = png.to_png();
+
+        let mut scene = WasmScene::new(4, 4);
+        assert_eq!(scene.add_image(&bytes), 0);
+        assert_eq!(scene.add_image(b"not an image"), -1);
+        assert_eq!(scene.sprite_count(), 1);
+    }
+
+    #[test]
+    fn test_get_sprite_corners() {
+        let mut scene = WasmScene::new(10, 10);
+        scene.add_rectangle(4, 2, 255, 0, 0, 255);
+        scene.set_sprite_anchor(0, 0.0, 0.0);
+        scene.set_sprite_position(0, 1.0, 2.0);
+
+        let corners = scene.get_sprite_corners(0);
+        assert_eq!(corners, vec![1.0, 2.0, 5.0, 2.0, 5.0, 4.0, 1.0, 4.0]);
+        assert!(scene.get_sprite_corners(5).is_empty());
+
+        // 角点为屏幕坐标，且查询不标记改动
+        scene.set_camera_position(1.0, 0.0);
+        scene.render();
+        let corners = scene.get_sprite_corners(0);
+        assert_eq!(corners[..2], [0.0, 2.0]);
+        assert!(!scene.scene.needs_render());
+    }
+
+    #[test]
+    fn test_sprite_ids_in_index_order() {
+        let mut scene = WasmScene::new(10, 10);
+        scene.add_rectangle(2, 2, 255, 0, 0, 255);
+        scene.add_rectangle(2, 2, 0, 255, 0, 255);
+        scene.add_rectangle(2, 2, 0, 0, 255, 255);
+
+        let ids = scene.sprite_ids();
+        assert_eq!(ids.len(), 3);
+        for (index, id) in ids.iter().enumerate() {
+            assert_eq!(*id as u64, scene.sprite_ids[index]);
+        }
+        // ID 按添加顺序递增
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_sprite_count_and_is_empty_consistent() {
+        let mut scene = WasmScene::new(10, 10);
+        let check = |scene: &WasmScene, expected: usize| {
+            assert_eq!(scene.sprite_count(), expected);
+            assert_eq!(scene.sprite_count(), scene.scene.sprite_count());
+            assert_eq!(scene.is_empty(), expected == 0);
+        };
+
+        check(&scene, 0);
+        scene.add_rectangle(2, 2, 255, 0, 0, 255);
+        check(&scene, 1);
+        scene.add_rectangle(2, 2, 0, 255, 0, 255);
+        check(&scene, 2);
+        scene.clear();
+        check(&scene, 0);
+    }
+
+    #[test]
+    fn test_get_background() {
+        let mut scene = WasmScene::new(10, 10);
+        scene.set_background_color(26, 26, 46, 255);
+        assert_eq!(scene.get_background(), 0x1A1A2EFF);
+    }
+
+    #[test]
+    fn test_update_sprite_buffer() {
+        let mut scene = WasmScene::new(4, 4);
+        let index = scene.add_rectangle(4, 4, 255, 0, 0, 255);
+        scene.set_sprite_position(index, 2.0, 2.0);
+
+        scene.render();
+        assert_eq!(&scene.scene.buffer()[0..4], &[255, 0, 0, 255]);
+
+        let green: Vec<u8> = [0, 255, 0, 255].repeat(16);
+        assert!(scene.update_sprite_buffer(index, &green));
+        scene.render();
+        assert_eq!(&scene.scene.buffer()[0..4], &[0, 255, 0, 255]);
+
+        // 长度错误或索引无效
+        assert!(!scene.update_sprite_buffer(index, &green[..8]));
+        assert!(!scene.update_sprite_buffer(5, &green));
+    }
+
+    #[test]
+    fn test_sprite_on_screen() {
+        let mut scene = WasmScene::new(100, 100);
+        let index = scene.add_rectangle(10, 10, 255, 0, 0, 255);
+
+        scene.set_sprite_position(index, 50.0, 50.0);
+        assert!(scene.is_sprite_on_screen(index));
+
+        scene.set_sprite_position(index, -50.0, 50.0);
+        assert!(!scene.is_sprite_on_screen(index));
+
+        // 无效索引
+        assert!(!scene.is_sprite_on_screen(99));
+    }
+
+    #[test]
+    fn test_pick_returns_index() {
+        let mut scene = WasmScene::new(10, 10);
+        scene.add_rectangle(4, 4, 255, 0, 0, 255);
+        scene.add_rectangle(4, 4, 0, 255, 0, 255);
+        scene.set_sprite_anchor(0, 0.0, 0.0);
+        scene.set_sprite_anchor(1, 0.0, 0.0);
+        scene.set_sprite_position(1, 5.0, 5.0);
+
+        assert_eq!(scene.pick(1.0, 1.0), 0);
+        assert_eq!(scene.pick(6.0, 6.0), 1);
+        assert_eq!(scene.pick(4.5, 4.5), -1);
+    }
+
+    #[test]
+    fn test_remove_middle_sprite_shifts_indices() {
+        let mut scene = WasmScene::new(10, 10);
+        for x in [0.0, 3.0, 6.0] {
+            let index = scene.add_rectangle(2, 2, 255, 255, 255, 255);
+            scene.set_sprite_anchor(index, 0.0, 0.0);
+            scene.set_sprite_position(index, x, 0.0);
+        }
+        let ids = scene.sprite_ids();
+
+        assert!(scene.remove_sprite(1));
+        assert_eq!(scene.sprite_count(), 2);
+        assert_eq!(scene.sprite_ids(), vec![ids[0], ids[2]]);
+        // 原索引 2 的精灵前移到索引 1
+        assert_eq!(scene.get_sprite_position_x(1), 6.0);
+
+        assert!(!scene.remove_sprite(2));
+        assert_eq!(scene.sprite_count(), 2);
+    }
+
+    #[test]
+    fn test_add_circle() {
+        let mut scene = WasmScene::new(10, 10);
+        scene.set_background_color(0, 0, 0, 255);
+        let index = scene.add_circle(5, 255, 255, 255, 255).unwrap();
+        assert_eq!(index, 0);
+        scene.set_sprite_anchor(index, 0.0, 0.0);
+        scene.render();
+
+        let buffer = scene.scene.buffer();
+        assert_eq!(buffer[(5 * 10 + 5) * 4], 255);
+        assert_eq!(buffer[0], 0);
+
+        // 半径过大时返回错误，不添加精灵
+        assert!(scene.add_circle(u32::MAX, 255, 255, 255, 255).is_err());
+        assert_eq!(scene.sprite_count(), 1);
+    }
+
+    #[test]
+    fn test_add_rounded_rectangle() {
+        let mut scene = WasmScene::new(10, 10);
+        let index = scene
+            .add_rounded_rectangle(10, 10, 3.0, 255, 255, 255, 255)
+            .unwrap();
+        assert_eq!(scene.sprite_count(), 1);
+        scene.set_sprite_anchor(index, 0.0, 0.0);
+        scene.render();
+
+        let buffer = scene.scene.buffer();
+        assert_eq!(buffer[(5 * 10 + 5) * 4], 255);
+        // 圆角处露出黑色背景
+        assert_eq!(buffer[0], 0);
+    }
+
+    #[test]
+    fn test_set_sprite_transform() {
+        let mut scene = WasmScene::new(10, 10);
+        scene.add_rectangle(2, 2, 255, 255, 255, 255);
+        scene.set_sprite_transform(0, 4.0, 5.0, 90.0, 2.0, 3.0);
+
+        assert_eq!(scene.get_sprite_position_x(0), 4.0);
+        assert_eq!(scene.get_sprite_position_y(0), 5.0);
+        assert!((scene.get_sprite_rotation(0) - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(scene.get_sprite_scale_x(0), 2.0);
+        assert_eq!(scene.get_sprite_scale_y(0), 3.0);
+
+        // 无效索引被忽略
+        scene.set_sprite_transform(5, 0.0, 0.0, 0.0, 1.0, 1.0);
+    }
+
+    #[test]
+    fn test_camera_pan_zoom_and_pick() {
+        let mut scene = WasmScene::new(10, 10);
+        scene.add_rectangle(2, 2, 255, 255, 255, 255);
+        scene.set_sprite_anchor(0, 0.0, 0.0);
+        scene.set_sprite_position(0, 20.0, 20.0);
+        assert_eq!(scene.pick(1.0, 1.0), -1);
+
+        scene.set_camera_position(19.0, 19.0);
+        scene.zoom_camera(2.0);
+        assert_eq!(
+            (scene.camera_x(), scene.camera_y(), scene.camera_zoom()),
+            (19.0, 19.0, 2.0)
+        );
+
+        // 世界坐标 (20, 20) 在屏幕 (2, 2)，放大后精灵占据屏幕 2..6
+        scene.render();
+        let buffer = scene.scene.buffer();
+        assert_eq!(buffer[(3 * 10 + 3) * 4], 255);
+        assert_eq!(buffer[(10 + 1) * 4], 0);
+        assert_eq!(scene.pick(5.5, 5.5), 0);
+
+        scene.pan_camera(10.0, 0.0);
+        assert_eq!(scene.pick(5.5, 5.5), -1);
+        scene.set_camera_zoom(-1.0);
+        assert_eq!(scene.camera_zoom(), 2.0);
+    }
+
+    #[test]
+    fn test_setters_report_invalid_index() {
+        let mut scene = WasmScene::new(4, 4);
+        scene.add_rectangle(2, 2, 255, 0, 0, 255);
+
+        assert!(scene.set_sprite_position(0, 1.0, 2.0));
+        assert!(scene.set_sprite_rotation(0, 90.0));
+        assert!(scene.set_sprite_scale(0, 2.0, 2.0));
+        assert!(scene.set_sprite_opacity(0, 0.5));
+        assert!(scene.set_sprite_sampling(0, 1));
+        assert!(scene.translate_sprite(0, 1.0, 0.0));
+        assert_eq!(scene.get_sprite_position_x(0), 2.0);
+
+        assert!(!scene.set_sprite_position(1, 1.0, 2.0));
+        assert!(!scene.set_sprite_transform(1, 0.0, 0.0, 0.0, 1.0, 1.0));
+        assert!(!scene.set_sprite_anchor(5, 0.0, 0.0));
+        assert!(!scene.set_sprite_opacity(1, 0.5));
+        assert!(!scene.reset_sprite_transform(1));
+        // 未知的采样模式同样返回 false
+        assert!(!scene.set_sprite_sampling(0, 7));
+
+        scene.remove_sprite(0);
+        assert!(!scene.set_sprite_rotation(0, 45.0));
+    }
+
+    #[test]
+    fn test_get_sprite_transform_snapshot() {
+        let mut scene = WasmScene::new(4, 4);
+        scene.add_rectangle(2, 2, 255, 0, 0, 255);
+        scene.set_sprite_transform(0, 1.0, 2.0, 90.0, 3.0, 4.0);
+        scene.set_sprite_anchor(0, 0.25, 0.75);
+
+        let t = scene.get_sprite_transform(0);
+        assert_eq!(t.len(), 7);
+        assert_eq!(t[0], scene.get_sprite_position_x(0));
+        assert_eq!(t[1], scene.get_sprite_position_y(0));
+        assert!((t[2] - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(&t[3..], &[3.0, 4.0, 0.25, 0.75]);
+
+        assert!(scene.get_sprite_transform(1).is_empty());
+    }
 }